@@ -0,0 +1,228 @@
+//! Measures the cost of recording barriers through `cmd::pipeline_barrier`,
+//! compared to the previous approach of collecting the native barriers into
+//! `Vec`s on every call.
+//!
+//! Commands are recorded into a device whose `vkCmdPipelineBarrier` does
+//! nothing, so only the time spent in this crate is measured. Run with
+//! `cargo bench`.
+
+use ash::vk;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ffi::{CStr, c_void};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "system" fn cmd_pipeline_barrier(
+	_command_buffer: vk::CommandBuffer,
+	_src_stage_mask: vk::PipelineStageFlags,
+	_dst_stage_mask: vk::PipelineStageFlags,
+	_dependency_flags: vk::DependencyFlags,
+	_memory_barrier_count: u32,
+	p_memory_barriers: *const vk::MemoryBarrier,
+	_buffer_memory_barrier_count: u32,
+	p_buffer_memory_barriers: *const vk::BufferMemoryBarrier,
+	_image_memory_barrier_count: u32,
+	p_image_memory_barriers: *const vk::ImageMemoryBarrier,
+) {
+	black_box((
+		p_memory_barriers,
+		p_buffer_memory_barriers,
+		p_image_memory_barriers,
+	));
+}
+
+fn null_device() -> ash::Device {
+	let load = |name: &CStr| -> *const c_void {
+		if name == c"vkCmdPipelineBarrier" {
+			cmd_pipeline_barrier as *const c_void
+		} else {
+			std::ptr::null()
+		}
+	};
+
+	unsafe { ash::Device::load_with(load, vk::Device::null()) }
+}
+
+/// The previous implementation of `cmd::pipeline_barrier`, allocating three
+/// `Vec`s per call.
+fn pipeline_barrier_vec(
+	device: &ash::Device,
+	command_buffer: vk::CommandBuffer,
+	global_barrier: Option<vk_sync::GlobalBarrier>,
+	buffer_barriers: &[vk_sync::BufferBarrier],
+	image_barriers: &[vk_sync::ImageBarrier],
+) {
+	let mut src_stage_mask = vk::PipelineStageFlags::TOP_OF_PIPE;
+	let mut dst_stage_mask = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
+
+	let mut vk_memory_barriers: Vec<vk::MemoryBarrier> = Vec::with_capacity(1);
+	let mut vk_buffer_barriers: Vec<vk::BufferMemoryBarrier> =
+		Vec::with_capacity(buffer_barriers.len());
+	let mut vk_image_barriers: Vec<vk::ImageMemoryBarrier> =
+		Vec::with_capacity(image_barriers.len());
+
+	if let Some(ref barrier) = global_barrier {
+		let (src_mask, dst_mask, barrier) = vk_sync::get_memory_barrier(barrier);
+		src_stage_mask |= src_mask;
+		dst_stage_mask |= dst_mask;
+		vk_memory_barriers.push(barrier);
+	}
+
+	for buffer_barrier in buffer_barriers {
+		let (src_mask, dst_mask, barrier) = vk_sync::get_buffer_memory_barrier(buffer_barrier);
+		src_stage_mask |= src_mask;
+		dst_stage_mask |= dst_mask;
+		vk_buffer_barriers.push(barrier);
+	}
+
+	for image_barrier in image_barriers {
+		let (src_mask, dst_mask, barrier) = vk_sync::get_image_memory_barrier(image_barrier);
+		src_stage_mask |= src_mask;
+		dst_stage_mask |= dst_mask;
+		vk_image_barriers.push(barrier);
+	}
+
+	unsafe {
+		device.cmd_pipeline_barrier(
+			command_buffer,
+			src_stage_mask,
+			dst_stage_mask,
+			vk::DependencyFlags::empty(),
+			&vk_memory_barriers,
+			&vk_buffer_barriers,
+			&vk_image_barriers,
+		);
+	}
+}
+
+/// The fastest round of one of the measured functions, which is less affected
+/// by other processes than the average.
+struct Measurement {
+	name: &'static str,
+	fastest: Duration,
+	allocations: usize,
+	calls: usize,
+}
+
+impl Measurement {
+	fn new(name: &'static str) -> Self {
+		Measurement {
+			name,
+			fastest: Duration::MAX,
+			allocations: 0,
+			calls: 0,
+		}
+	}
+
+	fn round(&mut self, iterations: usize, mut record: impl FnMut()) {
+		let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+		let start = Instant::now();
+		for _ in 0..iterations {
+			record();
+		}
+		self.fastest = self.fastest.min(start.elapsed());
+		self.allocations += ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+		self.calls += iterations;
+	}
+
+	fn report(&self, iterations: usize) {
+		println!(
+			"{:<24} {:>8.1} ns/call {:>6.2} allocations/call",
+			self.name,
+			self.fastest.as_nanos() as f64 / iterations as f64,
+			self.allocations as f64 / self.calls as f64
+		);
+	}
+}
+
+fn main() {
+	const ROUNDS: usize = 10;
+	const ITERATIONS: usize = 200_000;
+
+	let device = null_device();
+	let command_buffer = vk::CommandBuffer::null();
+
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::IndirectBuffer],
+	};
+	let buffer_barriers = vec![
+		vk_sync::BufferBarrier {
+			previous_accesses: &[vk_sync::AccessType::TransferWrite],
+			next_accesses: &[vk_sync::AccessType::VertexBuffer],
+			queue_transfer: vk_sync::QueueTransfer::Transfer { src: 0, dst: 1 },
+			buffer: vk::Buffer::null(),
+			offset: 0,
+			size: 256,
+		};
+		2
+	];
+	let image_barriers = vec![
+		vk_sync::ImageBarrier {
+			previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+			next_accesses: &[
+				vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer
+			],
+			previous_layout: vk_sync::ImageLayout::Optimal,
+			next_layout: vk_sync::ImageLayout::Optimal,
+			discard_contents: false,
+			queue_transfer: vk_sync::QueueTransfer::None,
+			image: vk::Image::null(),
+			range: vk::ImageSubresourceRange {
+				aspect_mask: vk::ImageAspectFlags::COLOR,
+				base_mip_level: 0,
+				level_count: 1,
+				base_array_layer: 0,
+				layer_count: 1,
+			},
+		};
+		4
+	];
+
+	// Rounds are interleaved, so that neither version benefits from running first
+	let mut vec = Measurement::new("Vec (previous)");
+	let mut inline = Measurement::new("cmd::pipeline_barrier");
+	for _ in 0..ROUNDS {
+		vec.round(ITERATIONS, || {
+			pipeline_barrier_vec(
+				&device,
+				command_buffer,
+				black_box(Some(global_barrier.clone())),
+				black_box(&buffer_barriers),
+				black_box(&image_barriers),
+			)
+		});
+		inline.round(ITERATIONS, || {
+			vk_sync::cmd::pipeline_barrier(
+				&device,
+				command_buffer,
+				black_box(Some(global_barrier.clone())),
+				black_box(&buffer_barriers),
+				black_box(&image_barriers),
+			)
+		});
+	}
+
+	vec.report(ITERATIONS);
+	inline.report(ITERATIONS);
+}
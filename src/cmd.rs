@@ -1,10 +1,84 @@
 use super::*;
 use ash;
+use std::mem::MaybeUninit;
+
+/// Number of buffer or image barriers that can be recorded by a single call
+/// without a heap allocation.
+const INLINE_BARRIER_CAPACITY: usize = 16;
+
+/// Stack-backed array of native barriers, which only falls back to the heap
+/// when more than `INLINE_BARRIER_CAPACITY` barriers are recorded at once.
+///
+/// The inline storage is left uninitialized, so that calls recording only a
+/// few barriers don't pay for initializing all of it.
+struct InlineArray<T> {
+	inline: [MaybeUninit<T>; INLINE_BARRIER_CAPACITY],
+	len: usize,
+	heap: Vec<T>,
+}
+
+impl<T: Copy> InlineArray<T> {
+	fn new(capacity: usize) -> Self {
+		InlineArray {
+			inline: [const { MaybeUninit::uninit() }; INLINE_BARRIER_CAPACITY],
+			len: 0,
+			heap: if capacity > INLINE_BARRIER_CAPACITY {
+				Vec::with_capacity(capacity)
+			} else {
+				Vec::new()
+			},
+		}
+	}
+
+	fn push(&mut self, value: T) {
+		if self.heap.capacity() > 0 {
+			self.heap.push(value);
+		} else if self.len < INLINE_BARRIER_CAPACITY {
+			self.inline[self.len].write(value);
+			self.len += 1;
+		} else {
+			let mut heap = Vec::with_capacity(INLINE_BARRIER_CAPACITY * 2);
+			heap.extend_from_slice(self.inline_slice());
+			heap.push(value);
+			self.heap = heap;
+		}
+	}
+
+	fn inline_slice(&self) -> &[T] {
+		// The first `len` elements have been written by `push`
+		unsafe { std::slice::from_raw_parts(self.inline.as_ptr().cast::<T>(), self.len) }
+	}
+
+	fn as_slice(&self) -> &[T] {
+		if self.heap.capacity() > 0 {
+			&self.heap
+		} else {
+			self.inline_slice()
+		}
+	}
+}
+
+/// Less common parameters of the `cmd` wrappers, e.g.
+/// `pipeline_barrier_with_options` or `set_event2`.
+#[derive(Debug, Default, Clone)]
+pub struct BarrierOptions<'a> {
+	/// An execution only dependency, recorded along with the other barriers -
+	/// see `ExecutionBarrier`.
+	pub execution_barrier: Option<ExecutionBarrier<'a>>,
+	/// Passed unmodified to `vkCmdPipelineBarrier` or the `vk::DependencyInfo`,
+	/// e.g. `BY_REGION`. The event commands don't take any dependency flags, so
+	/// these must be empty for `wait_events_with_options`, `set_event2` and
+	/// `wait_events2`.
+	pub dependency_flags: vk::DependencyFlags,
+}
 
 /// Simplified wrapper around `vkCmdPipelineBarrier`.
 /// The mapping functions defined above are used to translate the passed in
 /// barrier definitions into a set of pipeline stages and native Vulkan memory
 /// barriers to be passed to `vkCmdPipelineBarrier`.
+///
+/// Nothing is recorded if none of the barriers are required (see
+/// `is_barrier_required`), e.g. when only transitioning between read accesses.
 /// `command_buffer` is passed unmodified to `vkCmdPipelineBarrier`.
 pub fn pipeline_barrier(
 	device: &ash::Device,
@@ -13,22 +87,70 @@ pub fn pipeline_barrier(
 	buffer_barriers: &[BufferBarrier],
 	image_barriers: &[ImageBarrier],
 ) {
+	pipeline_barrier_with_options(
+		device,
+		command_buffer,
+		BarrierOptions::default(),
+		global_barrier,
+		buffer_barriers,
+		image_barriers,
+	);
+}
+
+/// Version of `pipeline_barrier` that also records an execution barrier and
+/// dependency flags - see `BarrierOptions`.
+/// An execution barrier only contributes pipeline stages, so passing one on its
+/// own records an execution dependency with no memory barriers.
+pub fn pipeline_barrier_with_options(
+	device: &ash::Device,
+	command_buffer: vk::CommandBuffer,
+	options: BarrierOptions,
+	global_barrier: Option<GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	let BarrierOptions {
+		execution_barrier,
+		dependency_flags,
+	} = options;
+
+	#[cfg(all(feature = "validation", debug_assertions))]
+	{
+		crate::validation::check_barriers(global_barrier.as_ref(), buffer_barriers, image_barriers);
+		crate::validation::check_dependency_flags(
+			dependency_flags,
+			execution_barrier.as_ref(),
+			global_barrier.as_ref(),
+			buffer_barriers,
+			image_barriers,
+		);
+	}
+
 	let mut src_stage_mask = vk::PipelineStageFlags::TOP_OF_PIPE;
 	let mut dst_stage_mask = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
+	let mut required = false;
 
-	// TODO: Optimize out the Vec heap allocations
-	let mut vk_memory_barriers: Vec<vk::MemoryBarrier> = Vec::with_capacity(1);
-	let mut vk_buffer_barriers: Vec<vk::BufferMemoryBarrier> =
-		Vec::with_capacity(buffer_barriers.len());
-	let mut vk_image_barriers: Vec<vk::ImageMemoryBarrier> =
-		Vec::with_capacity(image_barriers.len());
+	let mut vk_memory_barrier: Option<vk::MemoryBarrier> = None;
+	let mut vk_buffer_barriers: InlineArray<vk::BufferMemoryBarrier> =
+		InlineArray::new(buffer_barriers.len());
+	let mut vk_image_barriers: InlineArray<vk::ImageMemoryBarrier> =
+		InlineArray::new(image_barriers.len());
+
+	// Execution barrier
+	if let Some(ref barrier) = execution_barrier {
+		let (src_mask, dst_mask) = get_execution_barrier(barrier);
+		src_stage_mask |= src_mask;
+		dst_stage_mask |= dst_mask;
+		required |= is_execution_barrier_required(barrier);
+	}
 
 	// Global memory barrier
-	if let Some(ref barrier) = global_barrier {
-		let (src_mask, dst_mask, barrier) = get_memory_barrier(barrier);
+	if let Some(ref global_barrier) = global_barrier {
+		let (src_mask, dst_mask, barrier) = get_memory_barrier(global_barrier);
 		src_stage_mask |= src_mask;
 		dst_stage_mask |= dst_mask;
-		vk_memory_barriers.push(barrier);
+		vk_memory_barrier = Some(barrier);
+		required |= is_barrier_required(global_barrier);
 	}
 
 	// Buffer memory barriers
@@ -37,6 +159,7 @@ pub fn pipeline_barrier(
 		src_stage_mask |= src_mask;
 		dst_stage_mask |= dst_mask;
 		vk_buffer_barriers.push(barrier);
+		required |= is_buffer_barrier_required(buffer_barrier);
 	}
 
 	// Image memory barriers
@@ -44,22 +167,311 @@ pub fn pipeline_barrier(
 		let (src_mask, dst_mask, barrier) = get_image_memory_barrier(image_barrier);
 		src_stage_mask |= src_mask;
 		dst_stage_mask |= dst_mask;
+		required |=
+			is_mapped_image_barrier_required(image_barrier, barrier.old_layout, barrier.new_layout);
 		vk_image_barriers.push(barrier);
 	}
 
+	// Skip recording entirely if none of the barriers order anything
+	if !required {
+		return;
+	}
+
 	unsafe {
 		device.cmd_pipeline_barrier(
 			command_buffer,
 			src_stage_mask,
 			dst_stage_mask,
-			vk::DependencyFlags::empty(),
-			&vk_memory_barriers,
-			&vk_buffer_barriers,
-			&vk_image_barriers,
+			dependency_flags,
+			vk_memory_barrier.as_slice(),
+			vk_buffer_barriers.as_slice(),
+			vk_image_barriers.as_slice(),
 		);
 	}
 }
 
+/// Device function tables that can record synchronization2 commands.
+///
+/// This is implemented for `ash::Device` (core in Vulkan 1.3), and for
+/// `ash::khr::synchronization2::Device` when using `VK_KHR_synchronization2`.
+pub trait Synchronization2 {
+	/// Records `vkCmdPipelineBarrier2` (or `vkCmdPipelineBarrier2KHR`).
+	///
+	/// # Safety
+	///
+	/// The usual Vulkan valid usage rules for the command apply.
+	unsafe fn cmd_pipeline_barrier2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		dependency_info: &vk::DependencyInfo,
+	);
+
+	/// Records `vkCmdSetEvent2` (or `vkCmdSetEvent2KHR`).
+	///
+	/// # Safety
+	///
+	/// The usual Vulkan valid usage rules for the command apply.
+	unsafe fn cmd_set_event2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+		dependency_info: &vk::DependencyInfo,
+	);
+
+	/// Records `vkCmdResetEvent2` (or `vkCmdResetEvent2KHR`).
+	///
+	/// # Safety
+	///
+	/// The usual Vulkan valid usage rules for the command apply.
+	unsafe fn cmd_reset_event2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+		stage_mask: vk::PipelineStageFlags2,
+	);
+
+	/// Records `vkCmdWaitEvents2` (or `vkCmdWaitEvents2KHR`).
+	///
+	/// # Safety
+	///
+	/// The usual Vulkan valid usage rules for the command apply.
+	unsafe fn cmd_wait_events2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		events: &[vk::Event],
+		dependency_infos: &[vk::DependencyInfo],
+	);
+}
+
+impl Synchronization2 for ash::Device {
+	unsafe fn cmd_pipeline_barrier2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		dependency_info: &vk::DependencyInfo,
+	) {
+		unsafe { ash::Device::cmd_pipeline_barrier2(self, command_buffer, dependency_info) }
+	}
+
+	unsafe fn cmd_set_event2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+		dependency_info: &vk::DependencyInfo,
+	) {
+		unsafe { ash::Device::cmd_set_event2(self, command_buffer, event, dependency_info) }
+	}
+
+	unsafe fn cmd_reset_event2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+		stage_mask: vk::PipelineStageFlags2,
+	) {
+		unsafe { ash::Device::cmd_reset_event2(self, command_buffer, event, stage_mask) }
+	}
+
+	unsafe fn cmd_wait_events2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		events: &[vk::Event],
+		dependency_infos: &[vk::DependencyInfo],
+	) {
+		unsafe { ash::Device::cmd_wait_events2(self, command_buffer, events, dependency_infos) }
+	}
+}
+
+impl Synchronization2 for ash::khr::synchronization2::Device {
+	unsafe fn cmd_pipeline_barrier2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		dependency_info: &vk::DependencyInfo,
+	) {
+		unsafe {
+			ash::khr::synchronization2::Device::cmd_pipeline_barrier2(
+				self,
+				command_buffer,
+				dependency_info,
+			)
+		}
+	}
+
+	unsafe fn cmd_set_event2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+		dependency_info: &vk::DependencyInfo,
+	) {
+		unsafe {
+			ash::khr::synchronization2::Device::cmd_set_event2(
+				self,
+				command_buffer,
+				event,
+				dependency_info,
+			)
+		}
+	}
+
+	unsafe fn cmd_reset_event2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+		stage_mask: vk::PipelineStageFlags2,
+	) {
+		unsafe {
+			ash::khr::synchronization2::Device::cmd_reset_event2(
+				self,
+				command_buffer,
+				event,
+				stage_mask,
+			)
+		}
+	}
+
+	unsafe fn cmd_wait_events2(
+		&self,
+		command_buffer: vk::CommandBuffer,
+		events: &[vk::Event],
+		dependency_infos: &[vk::DependencyInfo],
+	) {
+		unsafe {
+			ash::khr::synchronization2::Device::cmd_wait_events2(
+				self,
+				command_buffer,
+				events,
+				dependency_infos,
+			)
+		}
+	}
+}
+
+/// Simplified wrapper around `vkCmdPipelineBarrier2`.
+/// The synchronization2 mapping functions are used to translate the passed in
+/// barrier definitions into native Vulkan memory barriers, which are recorded
+/// with a single `vk::DependencyInfo`.
+///
+/// Unlike `pipeline_barrier`, each barrier keeps its own source and destination
+/// stages, so unrelated barriers recorded in the same call don't stall each other.
+/// Nothing is recorded if none of the barriers are required (see
+/// `is_barrier_required`).
+/// `command_buffer` is passed unmodified to `vkCmdPipelineBarrier2`.
+pub fn pipeline_barrier2<D: Synchronization2 + ?Sized>(
+	device: &D,
+	command_buffer: vk::CommandBuffer,
+	global_barrier: Option<GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	pipeline_barrier2_with_options(
+		device,
+		command_buffer,
+		BarrierOptions::default(),
+		global_barrier,
+		buffer_barriers,
+		image_barriers,
+	);
+}
+
+/// Version of `pipeline_barrier2` that also records an execution barrier and
+/// dependency flags - see `BarrierOptions`.
+/// An execution barrier becomes a memory barrier without any access masks.
+pub fn pipeline_barrier2_with_options<D: Synchronization2 + ?Sized>(
+	device: &D,
+	command_buffer: vk::CommandBuffer,
+	options: BarrierOptions,
+	global_barrier: Option<GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	let BarrierOptions {
+		execution_barrier,
+		dependency_flags,
+	} = options;
+
+	#[cfg(all(feature = "validation", debug_assertions))]
+	{
+		crate::validation::check_barriers(global_barrier.as_ref(), buffer_barriers, image_barriers);
+		crate::validation::check_dependency_flags(
+			dependency_flags,
+			execution_barrier.as_ref(),
+			global_barrier.as_ref(),
+			buffer_barriers,
+			image_barriers,
+		);
+	}
+
+	with_dependency_info2(
+		execution_barrier.as_ref(),
+		global_barrier.as_ref(),
+		buffer_barriers,
+		image_barriers,
+		|dependency_info, required| {
+			// Skip recording entirely if none of the barriers order anything
+			if required {
+				unsafe {
+					device.cmd_pipeline_barrier2(
+						command_buffer,
+						&dependency_info.dependency_flags(dependency_flags),
+					);
+				}
+			}
+		},
+	);
+}
+
+/// Translates the barrier definitions into a `vk::DependencyInfo`, which is
+/// passed to `record` along with whether any of the barriers are required (see
+/// `is_barrier_required`). An execution barrier becomes a memory barrier
+/// without any access masks.
+fn with_dependency_info2(
+	execution_barrier: Option<&ExecutionBarrier>,
+	global_barrier: Option<&GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+	record: impl FnOnce(&vk::DependencyInfo, bool),
+) {
+	let mut required = false;
+
+	let mut vk_memory_barriers: InlineArray<vk::MemoryBarrier2> = InlineArray::new(2);
+	if let Some(barrier) = execution_barrier {
+		let mut memory_barrier = vk::MemoryBarrier2::default();
+		for previous_access in barrier.previous_accesses {
+			memory_barrier.src_stage_mask |= get_access_info(*previous_access).stage_mask2;
+		}
+		for next_access in barrier.next_accesses {
+			memory_barrier.dst_stage_mask |= get_access_info(*next_access).stage_mask2;
+		}
+		vk_memory_barriers.push(memory_barrier);
+		required |= is_execution_barrier_required(barrier);
+	}
+	if let Some(barrier) = global_barrier {
+		vk_memory_barriers.push(get_memory_barrier2(barrier));
+		required |= is_barrier_required(barrier);
+	}
+
+	let mut vk_buffer_barriers: InlineArray<vk::BufferMemoryBarrier2> =
+		InlineArray::new(buffer_barriers.len());
+	for buffer_barrier in buffer_barriers {
+		vk_buffer_barriers.push(get_buffer_memory_barrier2(buffer_barrier));
+		required |= is_buffer_barrier_required(buffer_barrier);
+	}
+
+	let mut vk_image_barriers: InlineArray<vk::ImageMemoryBarrier2> =
+		InlineArray::new(image_barriers.len());
+	for image_barrier in image_barriers {
+		let barrier = get_image_memory_barrier2(image_barrier);
+		required |=
+			is_mapped_image_barrier_required(image_barrier, barrier.old_layout, barrier.new_layout);
+		vk_image_barriers.push(barrier);
+	}
+
+	let dependency_info = vk::DependencyInfo::default()
+		.memory_barriers(vk_memory_barriers.as_slice())
+		.buffer_memory_barriers(vk_buffer_barriers.as_slice())
+		.image_memory_barriers(vk_image_barriers.as_slice());
+
+	record(&dependency_info, required);
+}
+
 /// Wrapper around `vkCmdSetEvent`.
 /// Sets an event when the accesses defined by `previous_accesses` are completed.
 /// `command_buffer` and `event` are passed unmodified to `vkCmdSetEvent`.
@@ -105,6 +517,9 @@ pub fn reset_event(
 /// barrier definitions into a set of pipeline stages and native Vulkan memory
 /// barriers to be passed to `vkCmdPipelineBarrier`.
 ///
+/// Unlike `pipeline_barrier`, this is always recorded even if none of the
+/// barriers are required, as waiting on the events is a dependency in itself.
+///
 /// `commandBuffer` and `events` are passed unmodified to `vkCmdWaitEvents`.
 pub fn wait_events(
 	device: &ash::Device,
@@ -113,23 +528,85 @@ pub fn wait_events(
 	global_barrier: Option<GlobalBarrier>,
 	buffer_barriers: &[BufferBarrier],
 	image_barriers: &[ImageBarrier],
+) {
+	wait_events_with_options(
+		device,
+		command_buffer,
+		events,
+		BarrierOptions::default(),
+		global_barrier,
+		buffer_barriers,
+		image_barriers,
+	);
+}
+
+/// Version of `wait_events` that also records an execution barrier, which only
+/// contributes pipeline stages - see `BarrierOptions`.
+///
+/// # Panics
+///
+/// Panics in debug builds if `options.dependency_flags` isn't empty.
+pub fn wait_events_with_options(
+	device: &ash::Device,
+	command_buffer: vk::CommandBuffer,
+	events: &[vk::Event],
+	options: BarrierOptions,
+	global_barrier: Option<GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	debug_assert!(
+		options.dependency_flags.is_empty(),
+		"vkCmdWaitEvents doesn't take dependency flags"
+	);
+
+	#[cfg(all(feature = "validation", debug_assertions))]
+	crate::validation::check_barriers(global_barrier.as_ref(), buffer_barriers, image_barriers);
+
+	record_wait_events(
+		device,
+		command_buffer,
+		events,
+		options.execution_barrier.as_ref(),
+		global_barrier.as_ref(),
+		buffer_barriers,
+		image_barriers,
+	);
+}
+
+/// Records `vkCmdWaitEvents` without validating the barriers, e.g. when they
+/// were already validated as the event was set.
+fn record_wait_events(
+	device: &ash::Device,
+	command_buffer: vk::CommandBuffer,
+	events: &[vk::Event],
+	execution_barrier: Option<&ExecutionBarrier>,
+	global_barrier: Option<&GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
 ) {
 	let mut src_stage_mask = vk::PipelineStageFlags::TOP_OF_PIPE;
 	let mut dst_stage_mask = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
 
-	// TODO: Optimize out the Vec heap allocations
-	let mut vk_memory_barriers: Vec<vk::MemoryBarrier> = Vec::with_capacity(1);
-	let mut vk_buffer_barriers: Vec<vk::BufferMemoryBarrier> =
-		Vec::with_capacity(buffer_barriers.len());
-	let mut vk_image_barriers: Vec<vk::ImageMemoryBarrier> =
-		Vec::with_capacity(image_barriers.len());
+	let mut vk_memory_barrier: Option<vk::MemoryBarrier> = None;
+	let mut vk_buffer_barriers: InlineArray<vk::BufferMemoryBarrier> =
+		InlineArray::new(buffer_barriers.len());
+	let mut vk_image_barriers: InlineArray<vk::ImageMemoryBarrier> =
+		InlineArray::new(image_barriers.len());
+
+	// Execution barrier
+	if let Some(barrier) = execution_barrier {
+		let (src_mask, dst_mask) = get_execution_barrier(barrier);
+		src_stage_mask |= src_mask;
+		dst_stage_mask |= dst_mask;
+	}
 
 	// Global memory barrier
-	if let Some(ref barrier) = global_barrier {
+	if let Some(barrier) = global_barrier {
 		let (src_mask, dst_mask, barrier) = get_memory_barrier(barrier);
 		src_stage_mask |= src_mask;
 		dst_stage_mask |= dst_mask;
-		vk_memory_barriers.push(barrier);
+		vk_memory_barrier = Some(barrier);
 	}
 
 	// Buffer memory barriers
@@ -154,9 +631,522 @@ pub fn wait_events(
 			events,
 			src_stage_mask,
 			dst_stage_mask,
-			&vk_memory_barriers,
-			&vk_buffer_barriers,
-			&vk_image_barriers,
+			vk_memory_barrier.as_slice(),
+			vk_buffer_barriers.as_slice(),
+			vk_image_barriers.as_slice(),
+		);
+	}
+}
+
+/// Simplified wrapper around `vkCmdSetEvent2`.
+/// The synchronization2 mapping functions are used to translate the passed in
+/// barrier definitions into the `vk::DependencyInfo` the event is set with.
+/// An execution barrier becomes a memory barrier without any access masks.
+///
+/// The same barrier definitions and options have to be passed to
+/// `wait_events2` when waiting on the event - see `SplitBarrier`, which
+/// guarantees this.
+/// `command_buffer` and `event` are passed unmodified to `vkCmdSetEvent2`.
+///
+/// # Panics
+///
+/// Panics in debug builds if `options.dependency_flags` isn't empty.
+pub fn set_event2<D: Synchronization2 + ?Sized>(
+	device: &D,
+	command_buffer: vk::CommandBuffer,
+	event: vk::Event,
+	options: BarrierOptions,
+	global_barrier: Option<GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	debug_assert!(
+		options.dependency_flags.is_empty(),
+		"vkCmdSetEvent2 doesn't take dependency flags"
+	);
+
+	#[cfg(all(feature = "validation", debug_assertions))]
+	crate::validation::check_barriers(global_barrier.as_ref(), buffer_barriers, image_barriers);
+
+	with_dependency_info2(
+		options.execution_barrier.as_ref(),
+		global_barrier.as_ref(),
+		buffer_barriers,
+		image_barriers,
+		|dependency_info, _| unsafe {
+			device.cmd_set_event2(command_buffer, event, dependency_info);
+		},
+	);
+}
+
+/// Wrapper around `vkCmdResetEvent2`.
+/// Resets an event when the accesses defined by `previous_accesses` are completed.
+/// Unlike `reset_event`, no stage is waited on if there are no previous accesses.
+/// `command_buffer` and `event` are passed unmodified to `vkCmdResetEvent2`.
+pub fn reset_event2<D: Synchronization2 + ?Sized>(
+	device: &D,
+	command_buffer: vk::CommandBuffer,
+	event: vk::Event,
+	previous_accesses: &[AccessType],
+) {
+	let mut stage_mask = vk::PipelineStageFlags2::NONE;
+	for previous_access in previous_accesses {
+		let previous_info = get_access_info(*previous_access);
+		stage_mask |= previous_info.stage_mask2;
+	}
+
+	unsafe {
+		device.cmd_reset_event2(command_buffer, event, stage_mask);
+	}
+}
+
+/// Simplified wrapper around `vkCmdWaitEvents2`.
+/// The barrier definitions are translated into a `vk::DependencyInfo`, which is
+/// passed once per event - so every event in `events` must have been set by
+/// `set_event2` with the same barrier definitions.
+///
+/// Like `wait_events`, this is always recorded even if none of the barriers
+/// are required.
+/// `command_buffer` and `events` are passed unmodified to `vkCmdWaitEvents2`.
+///
+/// # Panics
+///
+/// Panics in debug builds if `options.dependency_flags` isn't empty.
+pub fn wait_events2<D: Synchronization2 + ?Sized>(
+	device: &D,
+	command_buffer: vk::CommandBuffer,
+	events: &[vk::Event],
+	options: BarrierOptions,
+	global_barrier: Option<GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	debug_assert!(
+		options.dependency_flags.is_empty(),
+		"vkCmdWaitEvents2 doesn't take dependency flags"
+	);
+
+	#[cfg(all(feature = "validation", debug_assertions))]
+	crate::validation::check_barriers(global_barrier.as_ref(), buffer_barriers, image_barriers);
+
+	record_wait_events2(
+		device,
+		command_buffer,
+		events,
+		options.execution_barrier.as_ref(),
+		global_barrier.as_ref(),
+		buffer_barriers,
+		image_barriers,
+	);
+}
+
+/// Records `vkCmdWaitEvents2` without validating the barriers - see
+/// `record_wait_events`.
+fn record_wait_events2<D: Synchronization2 + ?Sized>(
+	device: &D,
+	command_buffer: vk::CommandBuffer,
+	events: &[vk::Event],
+	execution_barrier: Option<&ExecutionBarrier>,
+	global_barrier: Option<&GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	with_dependency_info2(
+		execution_barrier,
+		global_barrier,
+		buffer_barriers,
+		image_barriers,
+		|dependency_info, _| {
+			let mut dependency_infos: InlineArray<vk::DependencyInfo> =
+				InlineArray::new(events.len());
+			for _ in events {
+				dependency_infos.push(*dependency_info);
+			}
+
+			unsafe {
+				device.cmd_wait_events2(command_buffer, events, dependency_infos.as_slice());
+			}
+		},
+	);
+}
+
+/// A split barrier, recorded with a `vkCmdSetEvent` when the previous accesses
+/// are submitted, and a `vkCmdWaitEvents` right before the next accesses - so
+/// that unrelated work in between can overlap with the barrier.
+///
+/// Both commands derive their stage masks from the same barrier definitions,
+/// so the event is always set with the stages the wait expects. `begin`
+/// returns a `PendingSplitBarrier`, which must be ended with the same device
+/// function table.
+///
+/// With the `validation` feature, the barriers are only checked once, by
+/// `begin` or `begin2`.
+#[derive(Debug, Default, Clone)]
+pub struct SplitBarrier<'a> {
+	pub execution_barrier: Option<ExecutionBarrier<'a>>,
+	pub global_barrier: Option<GlobalBarrier<'a>>,
+	pub buffer_barriers: &'a [BufferBarrier<'a>],
+	pub image_barriers: &'a [ImageBarrier<'a>],
+}
+
+impl<'a> SplitBarrier<'a> {
+	/// Sets `event` once the previous accesses of every barrier are completed,
+	/// with `vkCmdSetEvent`.
+	pub fn begin(
+		self,
+		device: &ash::Device,
+		command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+	) -> PendingSplitBarrier<'a> {
+		#[cfg(all(feature = "validation", debug_assertions))]
+		crate::validation::check_barriers(
+			self.global_barrier.as_ref(),
+			self.buffer_barriers,
+			self.image_barriers,
+		);
+
+		let mut stage_mask = vk::PipelineStageFlags::TOP_OF_PIPE;
+		for previous_access in self.previous_accesses() {
+			stage_mask |= get_access_info(*previous_access).stage_mask;
+		}
+
+		unsafe {
+			device.cmd_set_event(command_buffer, event, stage_mask);
+		}
+
+		PendingSplitBarrier {
+			barrier: self,
+			event,
+		}
+	}
+
+	/// Sets `event` with `vkCmdSetEvent2`, passing the full dependency of the
+	/// barriers.
+	pub fn begin2<D: Synchronization2 + ?Sized>(
+		self,
+		device: &D,
+		command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+	) -> PendingSplitBarrier2<'a> {
+		set_event2(
+			device,
+			command_buffer,
+			event,
+			BarrierOptions {
+				execution_barrier: self.execution_barrier.clone(),
+				..Default::default()
+			},
+			self.global_barrier.clone(),
+			self.buffer_barriers,
+			self.image_barriers,
 		);
+
+		PendingSplitBarrier2 {
+			barrier: self,
+			event,
+		}
+	}
+
+	fn previous_accesses(&self) -> impl Iterator<Item = &AccessType> {
+		let execution = self
+			.execution_barrier
+			.iter()
+			.flat_map(|barrier| barrier.previous_accesses);
+		let global = self
+			.global_barrier
+			.iter()
+			.flat_map(|barrier| barrier.previous_accesses);
+		let buffers = self
+			.buffer_barriers
+			.iter()
+			.flat_map(|barrier| barrier.previous_accesses);
+		let images = self
+			.image_barriers
+			.iter()
+			.flat_map(|barrier| barrier.previous_accesses);
+
+		execution.chain(global).chain(buffers).chain(images)
 	}
 }
+
+/// A split barrier whose event was set with `vkCmdSetEvent`.
+#[must_use = "the split barrier has to be ended before the next accesses"]
+#[derive(Debug)]
+pub struct PendingSplitBarrier<'a> {
+	barrier: SplitBarrier<'a>,
+	event: vk::Event,
+}
+
+impl<'a> PendingSplitBarrier<'a> {
+	/// Returns the event that was set.
+	pub fn event(&self) -> vk::Event {
+		self.event
+	}
+
+	/// Waits on the event before the next accesses of every barrier, with
+	/// `vkCmdWaitEvents` - see `wait_events`.
+	pub fn end(self, device: &ash::Device, command_buffer: vk::CommandBuffer) -> SplitBarrier<'a> {
+		// The barriers were already validated by `begin`
+		record_wait_events(
+			device,
+			command_buffer,
+			&[self.event],
+			self.barrier.execution_barrier.as_ref(),
+			self.barrier.global_barrier.as_ref(),
+			self.barrier.buffer_barriers,
+			self.barrier.image_barriers,
+		);
+		self.barrier
+	}
+}
+
+/// A split barrier whose event was set with `vkCmdSetEvent2`.
+#[must_use = "the split barrier has to be ended before the next accesses"]
+#[derive(Debug)]
+pub struct PendingSplitBarrier2<'a> {
+	barrier: SplitBarrier<'a>,
+	event: vk::Event,
+}
+
+impl<'a> PendingSplitBarrier2<'a> {
+	/// Returns the event that was set.
+	pub fn event(&self) -> vk::Event {
+		self.event
+	}
+
+	/// Waits on the event before the next accesses of every barrier, with
+	/// `vkCmdWaitEvents2`. The dependency info is identical to the one the event
+	/// was set with, as the specification requires.
+	pub fn end2<D: Synchronization2 + ?Sized>(
+		self,
+		device: &D,
+		command_buffer: vk::CommandBuffer,
+	) -> SplitBarrier<'a> {
+		// The barriers were already validated by `begin2`
+		record_wait_events2(
+			device,
+			command_buffer,
+			&[self.event],
+			self.barrier.execution_barrier.as_ref(),
+			self.barrier.global_barrier.as_ref(),
+			self.barrier.buffer_barriers,
+			self.barrier.image_barriers,
+		);
+		self.barrier
+	}
+}
+
+/// Accumulates barriers over a pass boundary, so they can be recorded with a
+/// single `vkCmdPipelineBarrier`.
+///
+/// Barriers are translated with the mapping functions as they are pushed. All
+/// global barriers are merged into one memory barrier, and identical image
+/// barriers on the same image and subresource range are only recorded once.
+/// Barriers that aren't required (see `is_barrier_required`) are ignored.
+///
+/// The batch can be reused after flushing, keeping its allocations.
+#[derive(Debug, Default)]
+pub struct BarrierBatch {
+	src_stage_mask: vk::PipelineStageFlags,
+	dst_stage_mask: vk::PipelineStageFlags,
+	memory_barrier: Option<vk::MemoryBarrier<'static>>,
+	buffer_barriers: Vec<vk::BufferMemoryBarrier<'static>>,
+	image_barriers: Vec<vk::ImageMemoryBarrier<'static>>,
+	/// The number of buffer and image barriers pushed since the last flush,
+	/// including those that weren't required or were deduplicated - so that
+	/// validation messages refer to the index the caller pushed
+	pushed_buffers: usize,
+	pushed_images: usize,
+}
+
+impl BarrierBatch {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns true if nothing has been pushed since the batch was last flushed.
+	pub fn is_empty(&self) -> bool {
+		self.src_stage_mask.is_empty() && self.dst_stage_mask.is_empty()
+	}
+
+	/// Adds an execution barrier, which only contributes pipeline stages.
+	pub fn push_execution(&mut self, barrier: &ExecutionBarrier) {
+		if !is_execution_barrier_required(barrier) {
+			return;
+		}
+
+		let (src_mask, dst_mask) = get_execution_barrier(barrier);
+		self.src_stage_mask |= src_mask;
+		self.dst_stage_mask |= dst_mask;
+	}
+
+	/// Adds a global barrier, merging its accesses into the batch's single
+	/// memory barrier.
+	pub fn push_global(&mut self, barrier: &GlobalBarrier) {
+		#[cfg(all(feature = "validation", debug_assertions))]
+		crate::validation::report(
+			crate::validation::BarrierLocation::Global,
+			crate::validation::validate_global_barrier(barrier),
+		);
+
+		let (src_mask, dst_mask, barrier) = match get_required_memory_barrier(barrier) {
+			Some(mapped) => mapped,
+			None => return,
+		};
+		self.src_stage_mask |= src_mask;
+		self.dst_stage_mask |= dst_mask;
+
+		let memory_barrier = self
+			.memory_barrier
+			.get_or_insert_with(vk::MemoryBarrier::default);
+		memory_barrier.src_access_mask |= barrier.src_access_mask;
+		memory_barrier.dst_access_mask |= barrier.dst_access_mask;
+	}
+
+	/// Adds a buffer barrier.
+	pub fn push_buffer(&mut self, barrier: &BufferBarrier) {
+		#[cfg(all(feature = "validation", debug_assertions))]
+		crate::validation::report(
+			crate::validation::BarrierLocation::Buffer(self.pushed_buffers),
+			crate::validation::validate_buffer_barrier(barrier),
+		);
+		self.pushed_buffers += 1;
+
+		let (src_mask, dst_mask, barrier) = match get_required_buffer_memory_barrier(barrier) {
+			Some(mapped) => mapped,
+			None => return,
+		};
+		self.src_stage_mask |= src_mask;
+		self.dst_stage_mask |= dst_mask;
+
+		self.buffer_barriers.push(
+			vk::BufferMemoryBarrier::default()
+				.src_access_mask(barrier.src_access_mask)
+				.dst_access_mask(barrier.dst_access_mask)
+				.src_queue_family_index(barrier.src_queue_family_index)
+				.dst_queue_family_index(barrier.dst_queue_family_index)
+				.buffer(barrier.buffer)
+				.offset(barrier.offset)
+				.size(barrier.size),
+		);
+	}
+
+	/// Adds an image barrier, unless an identical barrier on the same image and
+	/// subresource range has already been pushed.
+	pub fn push_image(&mut self, barrier: &ImageBarrier) {
+		#[cfg(all(feature = "validation", debug_assertions))]
+		crate::validation::report(
+			crate::validation::BarrierLocation::Image(self.pushed_images),
+			crate::validation::validate_image_barrier(barrier),
+		);
+		self.pushed_images += 1;
+
+		let (src_mask, dst_mask, barrier) = match get_required_image_memory_barrier(barrier) {
+			Some(mapped) => mapped,
+			None => return,
+		};
+		self.src_stage_mask |= src_mask;
+		self.dst_stage_mask |= dst_mask;
+
+		let duplicate = self.image_barriers.iter().any(|existing| {
+			existing.image == barrier.image
+				&& is_same_range(&existing.subresource_range, &barrier.subresource_range)
+				&& existing.old_layout == barrier.old_layout
+				&& existing.new_layout == barrier.new_layout
+				&& existing.src_access_mask == barrier.src_access_mask
+				&& existing.dst_access_mask == barrier.dst_access_mask
+				&& existing.src_queue_family_index == barrier.src_queue_family_index
+				&& existing.dst_queue_family_index == barrier.dst_queue_family_index
+		});
+
+		if !duplicate {
+			self.image_barriers.push(
+				vk::ImageMemoryBarrier::default()
+					.src_access_mask(barrier.src_access_mask)
+					.dst_access_mask(barrier.dst_access_mask)
+					.old_layout(barrier.old_layout)
+					.new_layout(barrier.new_layout)
+					.src_queue_family_index(barrier.src_queue_family_index)
+					.dst_queue_family_index(barrier.dst_queue_family_index)
+					.image(barrier.image)
+					.subresource_range(barrier.subresource_range),
+			);
+		}
+	}
+
+	/// Returns the source and destination stages accumulated so far.
+	pub fn stage_masks(&self) -> (vk::PipelineStageFlags, vk::PipelineStageFlags) {
+		(self.src_stage_mask, self.dst_stage_mask)
+	}
+
+	/// Returns the merged memory barrier, if any global barriers were pushed.
+	pub fn memory_barrier(&self) -> Option<&vk::MemoryBarrier<'static>> {
+		self.memory_barrier.as_ref()
+	}
+
+	/// Returns the buffer memory barriers accumulated so far.
+	pub fn buffer_barriers(&self) -> &[vk::BufferMemoryBarrier<'static>] {
+		&self.buffer_barriers
+	}
+
+	/// Returns the image memory barriers accumulated so far.
+	pub fn image_barriers(&self) -> &[vk::ImageMemoryBarrier<'static>] {
+		&self.image_barriers
+	}
+
+	/// Discards everything pushed since the batch was last flushed.
+	pub fn clear(&mut self) {
+		self.src_stage_mask = vk::PipelineStageFlags::empty();
+		self.dst_stage_mask = vk::PipelineStageFlags::empty();
+		self.memory_barrier = None;
+		self.buffer_barriers.clear();
+		self.image_barriers.clear();
+		self.pushed_buffers = 0;
+		self.pushed_images = 0;
+	}
+
+	/// Records every barrier pushed since the last flush with a single
+	/// `vkCmdPipelineBarrier`, and clears the batch. Nothing is recorded if the
+	/// batch is empty.
+	/// `command_buffer` is passed unmodified to `vkCmdPipelineBarrier`.
+	pub fn flush(&mut self, device: &ash::Device, command_buffer: vk::CommandBuffer) {
+		self.flush_with_options(device, command_buffer, BarrierOptions::default());
+	}
+
+	/// Version of `flush` that also records an execution barrier (see
+	/// `push_execution`) and dependency flags - see `BarrierOptions`.
+	pub fn flush_with_options(
+		&mut self,
+		device: &ash::Device,
+		command_buffer: vk::CommandBuffer,
+		options: BarrierOptions,
+	) {
+		if let Some(ref barrier) = options.execution_barrier {
+			self.push_execution(barrier);
+		}
+
+		if !self.is_empty() {
+			unsafe {
+				device.cmd_pipeline_barrier(
+					command_buffer,
+					self.src_stage_mask,
+					self.dst_stage_mask,
+					options.dependency_flags,
+					self.memory_barrier.as_slice(),
+					&self.buffer_barriers,
+					&self.image_barriers,
+				);
+			}
+		}
+
+		self.clear();
+	}
+}
+
+fn is_same_range(a: &vk::ImageSubresourceRange, b: &vk::ImageSubresourceRange) -> bool {
+	a.aspect_mask == b.aspect_mask
+		&& a.base_mip_level == b.base_mip_level
+		&& a.level_count == b.level_count
+		&& a.base_array_layer == b.base_array_layer
+		&& a.layer_count == b.layer_count
+}
@@ -0,0 +1,408 @@
+//! A render graph built on access type declarations, enabled with the `graph`
+//! feature.
+//!
+//! Each pass declares the buffers and images it uses with `AccessType`s (and an
+//! `ImageLayout` for images). Compiling the graph orders the passes so that
+//! every hazard between them is respected, and computes the barriers required
+//! before each pass with a `tracker::ResourceTracker`. The computed barriers can
+//! be inspected without a device, or recorded through `cmd::pipeline_barrier`
+//! while executing the passes.
+
+use super::*;
+use crate::tracker::{Barriers, PendingBarriers, Resource, ResourceTracker};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Identifies a pass added to a `RenderGraph`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PassId(usize);
+
+impl PassId {
+	/// Returns the index of the pass, in the order passes were added.
+	pub fn index(self) -> usize {
+		self.0
+	}
+}
+
+/// Errors returned when compiling a `RenderGraph`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GraphError {
+	/// The dependencies between passes contain a cycle, so they can't be ordered
+	Cycle,
+
+	/// A pass uses an image that wasn't imported with `RenderGraph::import_image`
+	UnknownImage(vk::Image),
+}
+
+impl fmt::Display for GraphError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			GraphError::Cycle => write!(f, "the dependencies between passes contain a cycle"),
+			GraphError::UnknownImage(image) => {
+				write!(
+					f,
+					"image {:?} is used by a pass, but wasn't imported",
+					image
+				)
+			}
+		}
+	}
+}
+
+impl std::error::Error for GraphError {}
+
+type Callback<'a> = Box<dyn FnMut(&ash::Device, vk::CommandBuffer) + 'a>;
+
+#[derive(Debug, Clone)]
+struct ImageUse {
+	image: vk::Image,
+	accesses: Vec<AccessType>,
+	layout: ImageLayout,
+}
+
+#[derive(Debug, Clone)]
+struct BufferUse {
+	buffer: vk::Buffer,
+	accesses: Vec<AccessType>,
+}
+
+/// A pass of a `RenderGraph`, declaring the resources it uses and the callback
+/// recording its commands.
+///
+/// Each resource should only be declared once per pass, with every access the
+/// pass performs on it.
+pub struct Pass<'a> {
+	name: String,
+	images: Vec<ImageUse>,
+	buffers: Vec<BufferUse>,
+	callback: Option<Callback<'a>>,
+}
+
+impl<'a> Pass<'a> {
+	pub fn new(name: impl Into<String>) -> Self {
+		Pass {
+			name: name.into(),
+			images: Vec::new(),
+			buffers: Vec::new(),
+			callback: None,
+		}
+	}
+
+	/// Declares that the pass accesses `image` with `accesses`, in `layout`.
+	pub fn image(mut self, image: vk::Image, accesses: &[AccessType], layout: ImageLayout) -> Self {
+		self.images.push(ImageUse {
+			image,
+			accesses: accesses.to_vec(),
+			layout,
+		});
+		self
+	}
+
+	/// Declares that the pass accesses `buffer` with `accesses`.
+	pub fn buffer(mut self, buffer: vk::Buffer, accesses: &[AccessType]) -> Self {
+		self.buffers.push(BufferUse {
+			buffer,
+			accesses: accesses.to_vec(),
+		});
+		self
+	}
+
+	fn uses(&self, resource: Resource) -> bool {
+		match resource {
+			Resource::Image(image) => self.images.iter().any(|image_use| image_use.image == image),
+			Resource::Buffer(buffer) => self
+				.buffers
+				.iter()
+				.any(|buffer_use| buffer_use.buffer == buffer),
+		}
+	}
+
+	/// Sets the callback recording the commands of the pass, which is called by
+	/// `CompiledGraph::execute` once the pass's barriers have been recorded.
+	pub fn execute<F>(mut self, callback: F) -> Self
+	where
+		F: FnMut(&ash::Device, vk::CommandBuffer) + 'a,
+	{
+		self.callback = Some(Box::new(callback));
+		self
+	}
+}
+
+/// Collects passes and the initial state of the resources they use, to be
+/// ordered and compiled into a `CompiledGraph`.
+///
+/// Passes are ordered by the hazards between them, in the order they were
+/// added, plus any dependencies added explicitly with `add_dependency`.
+#[derive(Default)]
+pub struct RenderGraph<'a> {
+	tracker: ResourceTracker,
+	passes: Vec<Pass<'a>>,
+	dependencies: Vec<(PassId, PassId)>,
+	aliases: Vec<(Resource, Resource)>,
+}
+
+impl<'a> RenderGraph<'a> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a graph that continues from the resource state of a tracker, e.g.
+	/// the final state of the previous frame's graph.
+	pub fn with_tracker(tracker: ResourceTracker) -> Self {
+		RenderGraph {
+			tracker,
+			passes: Vec::new(),
+			dependencies: Vec::new(),
+			aliases: Vec::new(),
+		}
+	}
+
+	/// Imports an image used by the graph, whose contents were last accessed by
+	/// `accesses` in `layout` - see `ResourceTracker::register_image`.
+	pub fn import_image(
+		&mut self,
+		image: vk::Image,
+		range: vk::ImageSubresourceRange,
+		layout: ImageLayout,
+		accesses: &[AccessType],
+	) {
+		self.tracker.register_image(image, range, layout, accesses);
+	}
+
+	/// Imports a buffer used by the graph, whose contents were last accessed by
+	/// `accesses`. Buffers that aren't imported are assumed to have no previous
+	/// accesses.
+	pub fn import_buffer(&mut self, buffer: vk::Buffer, accesses: &[AccessType]) {
+		self.tracker.register_buffer(buffer, accesses);
+	}
+
+	/// Adds a pass to the graph.
+	pub fn add_pass(&mut self, pass: Pass<'a>) -> PassId {
+		self.passes.push(pass);
+		PassId(self.passes.len() - 1)
+	}
+
+	/// Requires `first` to be executed before `then`, regardless of the
+	/// resources they use.
+	pub fn add_dependency(&mut self, first: PassId, then: PassId) {
+		self.dependencies.push((first, then));
+	}
+
+	/// Declares that `incoming` reuses the memory of `outgoing`, e.g. when both
+	/// are transient resources bound to the same allocation.
+	///
+	/// Every pass using `outgoing` is executed before any pass using `incoming`,
+	/// and the first pass using `incoming` discards its contents after waiting on
+	/// the last accesses of `outgoing` - see `aliasing_image_barrier` and
+	/// `aliasing_buffer_barrier`.
+	pub fn add_alias(&mut self, outgoing: impl Into<Resource>, incoming: impl Into<Resource>) {
+		self.aliases.push((outgoing.into(), incoming.into()));
+	}
+
+	/// Orders the passes and computes the barriers required before each of them.
+	pub fn compile(self) -> Result<CompiledGraph<'a>, GraphError> {
+		let RenderGraph {
+			mut tracker,
+			passes,
+			mut dependencies,
+			aliases,
+		} = self;
+
+		for pass in &passes {
+			for image_use in &pass.images {
+				if tracker.last_accesses(image_use.image).is_none() {
+					return Err(GraphError::UnknownImage(image_use.image));
+				}
+			}
+		}
+
+		// Aliased resources can't be used at the same time
+		for (outgoing, incoming) in &aliases {
+			for (first, first_pass) in passes.iter().enumerate() {
+				for (then, then_pass) in passes.iter().enumerate() {
+					if first != then && first_pass.uses(*outgoing) && then_pass.uses(*incoming) {
+						dependencies.push((PassId(first), PassId(then)));
+					}
+				}
+			}
+		}
+
+		let order = order_passes(&passes, &dependencies)?;
+
+		// Incoming resources, which have yet to be used for the first time
+		let mut aliased: HashMap<Resource, Resource> = aliases
+			.iter()
+			.map(|(outgoing, incoming)| (*incoming, *outgoing))
+			.collect();
+
+		let mut barriers: Vec<PendingBarriers> = vec![PendingBarriers::default(); passes.len()];
+		for pass_id in &order {
+			let pass = &passes[pass_id.0];
+			for image_use in &pass.images {
+				match aliased.remove(&Resource::Image(image_use.image)) {
+					Some(outgoing) => tracker.transition_aliased_image(
+						outgoing,
+						image_use.image,
+						&image_use.accesses,
+						image_use.layout,
+					),
+					None => tracker.transition_image(
+						image_use.image,
+						&image_use.accesses,
+						image_use.layout,
+					),
+				}
+			}
+			for buffer_use in &pass.buffers {
+				match aliased.remove(&Resource::Buffer(buffer_use.buffer)) {
+					Some(outgoing) => tracker.transition_aliased_buffer(
+						outgoing,
+						buffer_use.buffer,
+						&buffer_use.accesses,
+					),
+					None => tracker.transition(buffer_use.buffer, &buffer_use.accesses),
+				}
+			}
+			barriers[pass_id.0] = tracker.take_barriers();
+		}
+
+		Ok(CompiledGraph {
+			tracker,
+			passes,
+			order,
+			barriers,
+		})
+	}
+}
+
+/// A render graph whose passes have been ordered, and the barriers between
+/// them computed.
+pub struct CompiledGraph<'a> {
+	tracker: ResourceTracker,
+	passes: Vec<Pass<'a>>,
+	order: Vec<PassId>,
+	barriers: Vec<PendingBarriers>,
+}
+
+impl CompiledGraph<'_> {
+	/// Returns the passes in the order they are executed.
+	pub fn order(&self) -> &[PassId] {
+		&self.order
+	}
+
+	/// Returns the name of a pass.
+	pub fn name(&self, pass: PassId) -> &str {
+		&self.passes[pass.0].name
+	}
+
+	/// Returns the barriers recorded before a pass.
+	pub fn barriers(&self, pass: PassId) -> Barriers<'_> {
+		self.barriers[pass.0].barriers()
+	}
+
+	/// Returns the tracker holding the state of every resource after the last
+	/// pass, which can be used to continue with another graph.
+	pub fn tracker(&self) -> &ResourceTracker {
+		&self.tracker
+	}
+
+	/// Consumes the graph, returning the tracker holding the state of every
+	/// resource after the last pass.
+	pub fn into_tracker(self) -> ResourceTracker {
+		self.tracker
+	}
+
+	/// Records every pass in order - first its barriers (see `Barriers::record`),
+	/// then its callback.
+	/// `command_buffer` is passed unmodified to the callbacks.
+	pub fn execute(&mut self, device: &ash::Device, command_buffer: vk::CommandBuffer) {
+		for pass_id in &self.order {
+			self.barriers[pass_id.0]
+				.barriers()
+				.record(device, command_buffer);
+
+			if let Some(callback) = self.passes[pass_id.0].callback.as_mut() {
+				callback(device, command_buffer);
+			}
+		}
+	}
+}
+
+/// Returns true if `later` has to be executed after `earlier`, because of a
+/// hazard or image layout transition on a resource they share.
+fn depends_on(earlier: &Pass, later: &Pass) -> bool {
+	let images = earlier.images.iter().any(|earlier_use| {
+		later.images.iter().any(|later_use| {
+			earlier_use.image == later_use.image
+				&& is_image_barrier_required(&ImageBarrier {
+					previous_accesses: &earlier_use.accesses,
+					next_accesses: &later_use.accesses,
+					previous_layout: earlier_use.layout,
+					next_layout: later_use.layout,
+					..Default::default()
+				})
+		})
+	});
+
+	let buffers = earlier.buffers.iter().any(|earlier_use| {
+		later.buffers.iter().any(|later_use| {
+			earlier_use.buffer == later_use.buffer
+				&& is_barrier_required(&GlobalBarrier {
+					previous_accesses: &earlier_use.accesses,
+					next_accesses: &later_use.accesses,
+				})
+		})
+	});
+
+	images || buffers
+}
+
+/// Topologically sorts the passes, preferring the order they were added in
+/// when passes don't depend on each other.
+fn order_passes(
+	passes: &[Pass],
+	dependencies: &[(PassId, PassId)],
+) -> Result<Vec<PassId>, GraphError> {
+	let mut edges: Vec<Vec<usize>> = vec![Vec::new(); passes.len()];
+	let mut in_degrees = vec![0usize; passes.len()];
+
+	let mut add_edge = |from: usize, to: usize| {
+		if !edges[from].contains(&to) {
+			edges[from].push(to);
+			in_degrees[to] += 1;
+		}
+	};
+
+	for (later, later_pass) in passes.iter().enumerate() {
+		for (earlier, earlier_pass) in passes[..later].iter().enumerate() {
+			if depends_on(earlier_pass, later_pass) {
+				add_edge(earlier, later);
+			}
+		}
+	}
+
+	for (first, then) in dependencies {
+		add_edge(first.0, then.0);
+	}
+
+	let mut ready: BTreeSet<usize> = (0..passes.len())
+		.filter(|pass| in_degrees[*pass] == 0)
+		.collect();
+	let mut order = Vec::with_capacity(passes.len());
+
+	while let Some(pass) = ready.pop_first() {
+		order.push(PassId(pass));
+		for next in &edges[pass] {
+			in_degrees[*next] -= 1;
+			if in_degrees[*next] == 0 {
+				ready.insert(*next);
+			}
+		}
+	}
+
+	if order.len() == passes.len() {
+		Ok(order)
+	} else {
+		Err(GraphError::Cycle)
+	}
+}
@@ -10,12 +10,23 @@
 //! Additionally, these usage types provide an easier mapping to other graphics
 //! APIs like DirectX 12.
 //!
-//! Use of other synchronization mechanisms such as semaphores, fences and render
-//! passes are not addressed in this library at present.
+//! Semaphore stage masks can be derived from the same usage types, see
+//! `semaphore_wait_stages` and the `submit` module, and so can subpass
+//! dependencies and attachment layouts, see the `render_pass` module. Other
+//! synchronization mechanisms such as fences are not addressed in this library
+//! at present.
 
 use ash::vk;
 
 pub mod cmd;
+#[cfg(feature = "graph")]
+pub mod graph;
+pub mod render_pass;
+pub mod rendering;
+pub mod submit;
+pub mod tracker;
+#[cfg(feature = "validation")]
+pub mod validation;
 
 /// Defines all potential resource usages
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
@@ -194,8 +205,91 @@ pub enum AccessType {
 	/// Read as an acceleration structure during acceleration structure building (e.g. a BLAS when building a TLAS)
 	AccelerationStructureBuildRead,
 
-	// Written as a buffer during acceleration structure building (e.g. a staging buffer)
+	/// Written as a buffer during acceleration structure building (e.g. a staging buffer)
 	AccelerationStructureBufferWrite,
+
+	/// Read as the source of a copy command (e.g. `vkCmdCopyBuffer`). Maps to the precise
+	/// `COPY` stage with synchronization2, and to `TRANSFER` otherwise
+	CopyRead,
+
+	/// Written as the destination of a copy command (e.g. `vkCmdCopyBufferToImage`)
+	CopyWrite,
+
+	/// Read as the source of a blit command (i.e. `vkCmdBlitImage`)
+	BlitRead,
+
+	/// Written as the destination of a blit command (i.e. `vkCmdBlitImage`)
+	BlitWrite,
+
+	/// Read as the source of a resolve command (i.e. `vkCmdResolveImage`)
+	ResolveRead,
+
+	/// Written as the destination of a resolve command (i.e. `vkCmdResolveImage`)
+	ResolveWrite,
+
+	/// Written by a clear command (e.g. `vkCmdClearColorImage` or `vkCmdFillBuffer`)
+	ClearWrite,
+
+	/// Read as a uniform buffer in a task shader. Requires `VK_EXT_mesh_shader` to be enabled.
+	TaskShaderReadUniformBuffer,
+
+	/// Read as a sampled image/uniform texel buffer in a task shader
+	TaskShaderReadSampledImageOrUniformTexelBuffer,
+
+	/// Read as any other resource in a task shader
+	TaskShaderReadOther,
+
+	/// Read as a uniform buffer in a mesh shader. Requires `VK_EXT_mesh_shader` to be enabled.
+	MeshShaderReadUniformBuffer,
+
+	/// Read as a sampled image/uniform texel buffer in a mesh shader
+	MeshShaderReadSampledImageOrUniformTexelBuffer,
+
+	/// Read as any other resource in a mesh shader
+	MeshShaderReadOther,
+
+	/// Written as any resource in a task shader
+	TaskShaderWrite,
+
+	/// Written as any resource in a mesh shader
+	MeshShaderWrite,
+
+	/// Read as a uniform buffer in a ray tracing shader
+	RayTracingShaderReadUniformBuffer,
+
+	/// Written as any resource in a ray tracing shader
+	RayTracingShaderWrite,
+
+	/// Read as a shader binding table during ray tracing dispatch
+	ShaderBindingTableRead,
+
+	/// Read as a geometry input buffer (vertex, index, transform or instance data) during
+	/// acceleration structure building
+	AccelerationStructureBuildInputRead,
+
+	/// Read as the source acceleration structure of a copy or serialization. With
+	/// synchronization2, maps to the `ACCELERATION_STRUCTURE_COPY_KHR` stage, which requires
+	/// `VK_KHR_ray_tracing_maintenance1` to be enabled.
+	AccelerationStructureCopyRead,
+
+	/// Written as the destination acceleration structure of a copy or deserialization. With
+	/// synchronization2, maps to the `ACCELERATION_STRUCTURE_COPY_KHR` stage, which requires
+	/// `VK_KHR_ray_tracing_maintenance1` to be enabled.
+	AccelerationStructureCopyWrite,
+
+	/// Written as the destination buffer of an acceleration structure serialization. With
+	/// synchronization2, maps to the `ACCELERATION_STRUCTURE_COPY_KHR` stage, which requires
+	/// `VK_KHR_ray_tracing_maintenance1` to be enabled.
+	AccelerationStructureSerializeWrite,
+
+	/// Read as the source buffer of an acceleration structure deserialization. With
+	/// synchronization2, maps to the `ACCELERATION_STRUCTURE_COPY_KHR` stage, which requires
+	/// `VK_KHR_ray_tracing_maintenance1` to be enabled.
+	AccelerationStructureDeserializeRead,
+
+	/// Written as a depth/stencil resolve attachment at the end of a subpass or dynamic
+	/// rendering. Like color resolves, this happens in the color attachment output stage.
+	DepthStencilResolveWrite,
 }
 
 /// Defines a handful of layout options for images.
@@ -217,6 +311,63 @@ pub enum ImageLayout {
 	GeneralAndPresentation,
 }
 
+/// Defines whether a buffer or image barrier transfers ownership of the
+/// resource between queue families, mapped to the queue family indices of the
+/// Vulkan barrier by `QueueTransfer::queue_family_indices`.
+///
+/// Defaults to `QueueTransfer::None`, so `..Default::default()` never creates
+/// an ownership transfer by accident.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum QueueTransfer {
+	/// No ownership transfer - both indices are `vk::QUEUE_FAMILY_IGNORED`.
+	#[default]
+	None,
+	/// Ownership transfer from the `src` to the `dst` queue family.
+	Transfer { src: u32, dst: u32 },
+	/// Ownership transfer to or from an external queue family, i.e. one outside
+	/// of the current instance but using the same device - `vk::QUEUE_FAMILY_EXTERNAL`.
+	External(ExternalTransfer),
+	/// Ownership transfer to or from a foreign queue family, i.e. a foreign
+	/// agent such as another API - `vk::QUEUE_FAMILY_FOREIGN_EXT`.
+	/// Requires `VK_EXT_queue_family_foreign` to be enabled.
+	Foreign(ExternalTransfer),
+}
+
+/// The direction of an ownership transfer with an external or foreign queue
+/// family.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ExternalTransfer {
+	/// Releases ownership from the `src` queue family.
+	Release { src: u32 },
+	/// Acquires ownership into the `dst` queue family.
+	Acquire { dst: u32 },
+}
+
+impl QueueTransfer {
+	/// Returns the source and destination queue family indices of the transfer.
+	pub fn queue_family_indices(self) -> (u32, u32) {
+		let (external, transfer) = match self {
+			QueueTransfer::None => {
+				return (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED);
+			}
+			QueueTransfer::Transfer { src, dst } => return (src, dst),
+			QueueTransfer::External(transfer) => (vk::QUEUE_FAMILY_EXTERNAL, transfer),
+			QueueTransfer::Foreign(transfer) => (vk::QUEUE_FAMILY_FOREIGN_EXT, transfer),
+		};
+
+		match transfer {
+			ExternalTransfer::Release { src } => (src, external),
+			ExternalTransfer::Acquire { dst } => (external, dst),
+		}
+	}
+
+	/// Returns true if ownership actually changes queue family.
+	pub fn is_transfer(self) -> bool {
+		let (src, dst) = self.queue_family_indices();
+		src != dst
+	}
+}
+
 /// Global barriers define a set of accesses on multiple resources at once.
 /// If a buffer or image doesn't require a queue ownership transfer, or an image
 /// doesn't require a layout transition (e.g. you're using one of the
@@ -229,6 +380,18 @@ pub struct GlobalBarrier<'a> {
 	pub next_accesses: &'a [AccessType],
 }
 
+/// Execution barriers define a dependency between the pipeline stages of the
+/// previous and next accesses, without making any memory available or visible.
+///
+/// These are only sufficient for write-after-read hazards, or when memory
+/// dependencies are already handled elsewhere (e.g. by a semaphore) - prefer
+/// global barriers at all other times.
+#[derive(Debug, Default, Clone)]
+pub struct ExecutionBarrier<'a> {
+	pub previous_accesses: &'a [AccessType],
+	pub next_accesses: &'a [AccessType],
+}
+
 /// Buffer barriers should only be used when a queue family ownership transfer
 /// is required - prefer global barriers at all other times.
 ///
@@ -236,8 +399,8 @@ pub struct GlobalBarrier<'a> {
 /// they only affect the buffer range identified by `buffer`, `offset` and `size`,
 /// rather than all resources.
 ///
-/// `src_queue_family_index` and `dst_queue_family_index` will be passed unmodified
-/// into a buffer memory barrier.
+/// `queue_transfer` is mapped to the queue family indices of the buffer memory
+/// barrier.
 ///
 /// A buffer barrier defining a queue ownership transfer needs to be executed
 /// twice - once by a queue in the source queue family, and then once again by a
@@ -247,8 +410,7 @@ pub struct GlobalBarrier<'a> {
 pub struct BufferBarrier<'a> {
 	pub previous_accesses: &'a [AccessType],
 	pub next_accesses: &'a [AccessType],
-	pub src_queue_family_index: u32,
-	pub dst_queue_family_index: u32,
+	pub queue_transfer: QueueTransfer,
 	pub buffer: vk::Buffer,
 	pub offset: usize,
 	pub size: usize,
@@ -266,8 +428,8 @@ pub struct BufferBarrier<'a> {
 /// they only affect the image subresource range identified by `image` and
 /// `range`, rather than all resources.
 ///
-/// `src_queue_family_index`, `dst_queue_family_index`, `image`, and `range` will
-/// be passed unmodified into an image memory barrier.
+/// `image` and `range` will be passed unmodified into an image memory barrier,
+/// and `queue_transfer` is mapped to its queue family indices.
 ///
 /// An image barrier defining a queue ownership transfer needs to be executed
 /// twice - once by a queue in the source queue family, and then once again by a
@@ -287,15 +449,73 @@ pub struct ImageBarrier<'a> {
 	pub previous_layout: ImageLayout,
 	pub next_layout: ImageLayout,
 	pub discard_contents: bool,
-	pub src_queue_family_index: u32,
-	pub dst_queue_family_index: u32,
+	pub queue_transfer: QueueTransfer,
 	pub image: vk::Image,
 	pub range: vk::ImageSubresourceRange,
 }
 
+/// Errors that can be detected when translating barrier definitions into
+/// native Vulkan barriers.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BarrierError {
+	/// The accesses on one side of an image barrier imply different image layouts
+	ConflictingLayouts {
+		first: vk::ImageLayout,
+		second: vk::ImageLayout,
+	},
+
+	/// More than one write access was specified in a single set of accesses
+	MultipleWriteAccesses {
+		first: AccessType,
+		second: AccessType,
+	},
+
+	/// A write access was specified alongside read accesses in a single set of accesses
+	WriteMixedWithReads { write: AccessType, read: AccessType },
+
+	/// An access that can only be performed on images was used in a buffer barrier
+	ImageOnlyAccessOnBuffer(AccessType),
+
+	/// `AccessType::Present` was used in a global barrier - presentation only applies to images
+	PresentInGlobalBarrier,
+}
+
+impl std::fmt::Display for BarrierError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BarrierError::ConflictingLayouts { first, second } => write!(
+				f,
+				"accesses imply conflicting image layouts {:?} and {:?}",
+				first, second
+			),
+			BarrierError::MultipleWriteAccesses { first, second } => write!(
+				f,
+				"multiple write accesses {:?} and {:?} in one set of accesses",
+				first, second
+			),
+			BarrierError::WriteMixedWithReads { write, read } => write!(
+				f,
+				"write access {:?} mixed with read access {:?} in one set of accesses",
+				write, read
+			),
+			BarrierError::ImageOnlyAccessOnBuffer(access) => {
+				write!(f, "image only access {:?} used on a buffer", access)
+			}
+			BarrierError::PresentInGlobalBarrier => {
+				write!(f, "AccessType::Present used in a global barrier")
+			}
+		}
+	}
+}
+
+impl std::error::Error for BarrierError {}
+
 /// Mapping function that translates a global barrier into a set of source and
 /// destination pipeline stages, and a memory barrier, that can be used with
 /// Vulkan synchronization methods.
+///
+/// Invalid barrier definitions are translated as they are - see
+/// `try_get_memory_barrier` to detect them.
 pub fn get_memory_barrier<'a>(
 	barrier: &GlobalBarrier<'a>,
 ) -> (
@@ -344,9 +564,29 @@ pub fn get_memory_barrier<'a>(
 	(src_stages, dst_stages, memory_barrier)
 }
 
+/// Fallible version of `get_memory_barrier`, returning an error rather than
+/// translating an invalid barrier definition.
+pub fn try_get_memory_barrier<'a>(
+	barrier: &GlobalBarrier<'a>,
+) -> Result<
+	(
+		vk::PipelineStageFlags,
+		vk::PipelineStageFlags,
+		vk::MemoryBarrier<'a>,
+	),
+	BarrierError,
+> {
+	validate_global_barrier(barrier)?;
+
+	Ok(get_memory_barrier(barrier))
+}
+
 /// Mapping function that translates a buffer barrier into a set of source and
 /// destination pipeline stages, and a buffer memory barrier, that can be used
 /// with Vulkan synchronization methods.
+///
+/// Invalid barrier definitions are translated as they are - see
+/// `try_get_buffer_memory_barrier` to detect them.
 pub fn get_buffer_memory_barrier<'a>(
 	barrier: &BufferBarrier<'a>,
 ) -> (
@@ -357,9 +597,12 @@ pub fn get_buffer_memory_barrier<'a>(
 	let mut src_stages = vk::PipelineStageFlags::empty();
 	let mut dst_stages = vk::PipelineStageFlags::empty();
 
+	let (src_queue_family_index, dst_queue_family_index) =
+		barrier.queue_transfer.queue_family_indices();
+
 	let mut buffer_barrier = vk::BufferMemoryBarrier {
-		src_queue_family_index: barrier.src_queue_family_index,
-		dst_queue_family_index: barrier.dst_queue_family_index,
+		src_queue_family_index,
+		dst_queue_family_index,
 		buffer: barrier.buffer,
 		offset: barrier.offset as u64,
 		size: barrier.size as u64,
@@ -402,24 +645,78 @@ pub fn get_buffer_memory_barrier<'a>(
 	(src_stages, dst_stages, buffer_barrier)
 }
 
+/// Fallible version of `get_buffer_memory_barrier`, returning an error rather
+/// than translating an invalid barrier definition.
+pub fn try_get_buffer_memory_barrier<'a>(
+	barrier: &BufferBarrier<'a>,
+) -> Result<
+	(
+		vk::PipelineStageFlags,
+		vk::PipelineStageFlags,
+		vk::BufferMemoryBarrier<'a>,
+	),
+	BarrierError,
+> {
+	validate_buffer_barrier(barrier)?;
+
+	Ok(get_buffer_memory_barrier(barrier))
+}
+
 /// Mapping function that translates an image barrier into a set of source and
 /// destination pipeline stages, and an image memory barrier, that can be used
 /// with Vulkan synchronization methods.
+///
+/// Invalid barrier definitions are translated as they are, with the last access
+/// on each side determining the image layout - see `try_get_image_memory_barrier`
+/// to detect them.
 pub fn get_image_memory_barrier<'a>(
 	barrier: &ImageBarrier<'a>,
 ) -> (
 	vk::PipelineStageFlags,
 	vk::PipelineStageFlags,
 	vk::ImageMemoryBarrier<'a>,
+) {
+	map_image_memory_barrier(barrier, get_unchecked_image_barrier_layouts(barrier))
+}
+
+/// Fallible version of `get_image_memory_barrier`, returning an error rather
+/// than translating an invalid barrier definition.
+pub fn try_get_image_memory_barrier<'a>(
+	barrier: &ImageBarrier<'a>,
+) -> Result<
+	(
+		vk::PipelineStageFlags,
+		vk::PipelineStageFlags,
+		vk::ImageMemoryBarrier<'a>,
+	),
+	BarrierError,
+> {
+	let layouts = get_image_barrier_layouts(barrier)?;
+
+	Ok(map_image_memory_barrier(barrier, layouts))
+}
+
+fn map_image_memory_barrier<'a>(
+	barrier: &ImageBarrier<'a>,
+	(old_layout, new_layout): (vk::ImageLayout, vk::ImageLayout),
+) -> (
+	vk::PipelineStageFlags,
+	vk::PipelineStageFlags,
+	vk::ImageMemoryBarrier<'a>,
 ) {
 	let mut src_stages = vk::PipelineStageFlags::empty();
 	let mut dst_stages = vk::PipelineStageFlags::empty();
 
+	let (src_queue_family_index, dst_queue_family_index) =
+		barrier.queue_transfer.queue_family_indices();
+
 	let mut image_barrier = vk::ImageMemoryBarrier {
-		src_queue_family_index: barrier.src_queue_family_index,
-		dst_queue_family_index: barrier.dst_queue_family_index,
+		src_queue_family_index,
+		dst_queue_family_index,
 		image: barrier.image,
 		subresource_range: barrier.range,
+		old_layout,
+		new_layout,
 		..Default::default()
 	};
 
@@ -432,27 +729,6 @@ pub fn get_image_memory_barrier<'a>(
 		if is_write_access(*previous_access) {
 			image_barrier.src_access_mask |= previous_info.access_mask;
 		}
-
-		if barrier.discard_contents {
-			image_barrier.old_layout = vk::ImageLayout::UNDEFINED;
-		} else {
-			let layout = match barrier.previous_layout {
-				ImageLayout::General => {
-					if *previous_access == AccessType::Present {
-						vk::ImageLayout::PRESENT_SRC_KHR
-					} else {
-						vk::ImageLayout::GENERAL
-					}
-				}
-				ImageLayout::Optimal => previous_info.image_layout,
-				ImageLayout::GeneralAndPresentation => {
-					unimplemented!()
-					// TODO: layout = vk::ImageLayout::VK_IMAGE_LAYOUT_SHARED_PRESENT_KHR
-				}
-			};
-
-			image_barrier.old_layout = layout;
-		}
 	}
 
 	for next_access in barrier.next_accesses {
@@ -466,23 +742,35 @@ pub fn get_image_memory_barrier<'a>(
 		if image_barrier.src_access_mask != vk::AccessFlags::empty() {
 			image_barrier.dst_access_mask |= next_info.access_mask;
 		}
+	}
 
-		let layout = match barrier.next_layout {
-			ImageLayout::General => {
-				if *next_access == AccessType::Present {
-					vk::ImageLayout::PRESENT_SRC_KHR
-				} else {
-					vk::ImageLayout::GENERAL
-				}
-			}
-			ImageLayout::Optimal => next_info.image_layout,
-			ImageLayout::GeneralAndPresentation => {
-				unimplemented!()
-				// TODO: layout = vk::ImageLayout::VK_IMAGE_LAYOUT_SHARED_PRESENT_KHR
-			}
-		};
+	// Ensure that the stage masks are valid if no stages were determined
+	if src_stages == vk::PipelineStageFlags::empty() {
+		src_stages = vk::PipelineStageFlags::TOP_OF_PIPE;
+	}
+
+	if dst_stages == vk::PipelineStageFlags::empty() {
+		dst_stages = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
+	}
+
+	(src_stages, dst_stages, image_barrier)
+}
+
+/// Mapping function that translates an execution barrier into a set of source
+/// and destination pipeline stages, that can be used with Vulkan synchronization
+/// methods. No memory barrier is produced.
+pub fn get_execution_barrier(
+	barrier: &ExecutionBarrier,
+) -> (vk::PipelineStageFlags, vk::PipelineStageFlags) {
+	let mut src_stages = vk::PipelineStageFlags::empty();
+	let mut dst_stages = vk::PipelineStageFlags::empty();
 
-		image_barrier.new_layout = layout;
+	for previous_access in barrier.previous_accesses {
+		src_stages |= get_access_info(*previous_access).stage_mask;
+	}
+
+	for next_access in barrier.next_accesses {
+		dst_stages |= get_access_info(*next_access).stage_mask;
 	}
 
 	// Ensure that the stage masks are valid if no stages were determined
@@ -494,13 +782,695 @@ pub fn get_image_memory_barrier<'a>(
 		dst_stages = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
 	}
 
-	(src_stages, dst_stages, image_barrier)
+	(src_stages, dst_stages)
+}
+
+/// Returns true if a global barrier orders anything - i.e. there is a previous
+/// or next write access, and accesses on both sides of the barrier.
+///
+/// Barriers between read only accesses (or with `AccessType::Nothing` on either
+/// side) need neither an execution nor a memory dependency, and can be skipped.
+pub fn is_barrier_required(barrier: &GlobalBarrier) -> bool {
+	is_hazard(barrier.previous_accesses, barrier.next_accesses)
+}
+
+/// Returns true if an execution barrier orders anything - see `is_barrier_required`.
+pub fn is_execution_barrier_required(barrier: &ExecutionBarrier) -> bool {
+	is_hazard(barrier.previous_accesses, barrier.next_accesses)
+}
+
+/// Returns true if a buffer barrier is required - either because it orders
+/// accesses (see `is_barrier_required`), or because it transfers queue family
+/// ownership.
+pub fn is_buffer_barrier_required(barrier: &BufferBarrier) -> bool {
+	barrier.queue_transfer.is_transfer()
+		|| is_hazard(barrier.previous_accesses, barrier.next_accesses)
+}
+
+/// Returns true if an image barrier is required - either because it orders
+/// accesses (see `is_barrier_required`), transitions the image layout, or
+/// transfers queue family ownership.
+///
+/// Invalid barriers are always reported as required, so that they aren't
+/// silently dropped.
+pub fn is_image_barrier_required(barrier: &ImageBarrier) -> bool {
+	match get_image_barrier_layouts(barrier) {
+		Ok((old_layout, new_layout)) => {
+			is_mapped_image_barrier_required(barrier, old_layout, new_layout)
+		}
+		Err(_) => true,
+	}
+}
+
+/// Version of `get_memory_barrier` that returns `None` if the barrier isn't
+/// required - see `is_barrier_required`.
+pub fn get_required_memory_barrier<'a>(
+	barrier: &GlobalBarrier<'a>,
+) -> Option<(
+	vk::PipelineStageFlags,
+	vk::PipelineStageFlags,
+	vk::MemoryBarrier<'a>,
+)> {
+	if is_barrier_required(barrier) {
+		Some(get_memory_barrier(barrier))
+	} else {
+		None
+	}
+}
+
+/// Version of `get_buffer_memory_barrier` that returns `None` if the barrier
+/// isn't required - see `is_buffer_barrier_required`.
+pub fn get_required_buffer_memory_barrier<'a>(
+	barrier: &BufferBarrier<'a>,
+) -> Option<(
+	vk::PipelineStageFlags,
+	vk::PipelineStageFlags,
+	vk::BufferMemoryBarrier<'a>,
+)> {
+	if is_buffer_barrier_required(barrier) {
+		Some(get_buffer_memory_barrier(barrier))
+	} else {
+		None
+	}
+}
+
+/// Version of `get_image_memory_barrier` that returns `None` if the barrier
+/// isn't required - see `is_image_barrier_required`.
+pub fn get_required_image_memory_barrier<'a>(
+	barrier: &ImageBarrier<'a>,
+) -> Option<(
+	vk::PipelineStageFlags,
+	vk::PipelineStageFlags,
+	vk::ImageMemoryBarrier<'a>,
+)> {
+	if is_image_barrier_required(barrier) {
+		Some(get_image_memory_barrier(barrier))
+	} else {
+		None
+	}
+}
+
+/// Builds the barrier required when `image` starts reusing memory that was
+/// previously bound to another (now unused) resource, e.g. when transient
+/// resources are aliased.
+///
+/// `previous_accesses` are the last accesses of the outgoing resource, and
+/// `next_accesses` the first accesses of the incoming image. The contents of
+/// the image are discarded, so the old layout is always `UNDEFINED` and
+/// `previous_layout` is left at its default.
+pub fn aliasing_image_barrier<'a>(
+	previous_accesses: &'a [AccessType],
+	next_accesses: &'a [AccessType],
+	next_layout: ImageLayout,
+	image: vk::Image,
+	range: vk::ImageSubresourceRange,
+) -> ImageBarrier<'a> {
+	ImageBarrier {
+		previous_accesses,
+		next_accesses,
+		previous_layout: ImageLayout::default(),
+		next_layout,
+		discard_contents: true,
+		queue_transfer: QueueTransfer::None,
+		image,
+		range,
+	}
+}
+
+/// Builds the barrier required when a buffer starts reusing memory that was
+/// previously bound to another (now unused) resource - see
+/// `aliasing_image_barrier`.
+///
+/// Buffers have no layout to transition, so this only needs a memory dependency
+/// and is a global barrier. Unlike a buffer barrier, this also accepts the image
+/// only accesses of an outgoing image.
+pub fn aliasing_buffer_barrier<'a>(
+	previous_accesses: &'a [AccessType],
+	next_accesses: &'a [AccessType],
+) -> GlobalBarrier<'a> {
+	GlobalBarrier {
+		previous_accesses,
+		next_accesses,
+	}
+}
+
+/// Mapping function that splits a buffer barrier defining a queue ownership
+/// transfer into its release and acquire halves, returned as `(release, acquire)`.
+///
+/// The release half is recorded on a queue in the source queue family, with an
+/// empty destination access mask, and the acquire half on a queue in the
+/// destination queue family, with an empty source access mask. A semaphore must
+/// guarantee execution order between them.
+///
+/// # Panics
+///
+/// Panics in debug builds if the barrier doesn't define a queue family ownership
+/// transfer - see `QueueTransfer::is_transfer`.
+pub fn ownership_transfer_buffer<'a>(
+	barrier: &BufferBarrier<'a>,
+) -> (
+	(
+		vk::PipelineStageFlags,
+		vk::PipelineStageFlags,
+		vk::BufferMemoryBarrier<'a>,
+	),
+	(
+		vk::PipelineStageFlags,
+		vk::PipelineStageFlags,
+		vk::BufferMemoryBarrier<'a>,
+	),
+) {
+	debug_assert!(
+		barrier.queue_transfer.is_transfer(),
+		"Barrier doesn't define a queue family ownership transfer"
+	);
+	let (src_stages, dst_stages, buffer_barrier) = get_buffer_memory_barrier(barrier);
+
+	let release = buffer_barrier.dst_access_mask(vk::AccessFlags::empty());
+	let acquire = buffer_barrier
+		.src_access_mask(vk::AccessFlags::empty())
+		.dst_access_mask(get_next_access_mask(barrier.next_accesses));
+
+	(
+		(src_stages, vk::PipelineStageFlags::BOTTOM_OF_PIPE, release),
+		(vk::PipelineStageFlags::TOP_OF_PIPE, dst_stages, acquire),
+	)
+}
+
+/// Mapping function that splits an image barrier defining a queue ownership
+/// transfer into its release and acquire halves - see
+/// `ownership_transfer_buffer`.
+///
+/// Both halves contain the same layout transition, which is only executed once.
+///
+/// # Panics
+///
+/// Panics in debug builds if the barrier doesn't define a queue family ownership
+/// transfer - see `QueueTransfer::is_transfer`.
+pub fn ownership_transfer_image<'a>(
+	barrier: &ImageBarrier<'a>,
+) -> (
+	(
+		vk::PipelineStageFlags,
+		vk::PipelineStageFlags,
+		vk::ImageMemoryBarrier<'a>,
+	),
+	(
+		vk::PipelineStageFlags,
+		vk::PipelineStageFlags,
+		vk::ImageMemoryBarrier<'a>,
+	),
+) {
+	debug_assert!(
+		barrier.queue_transfer.is_transfer(),
+		"Barrier doesn't define a queue family ownership transfer"
+	);
+	let (src_stages, dst_stages, image_barrier) = get_image_memory_barrier(barrier);
+
+	let release = image_barrier.dst_access_mask(vk::AccessFlags::empty());
+	let acquire = image_barrier
+		.src_access_mask(vk::AccessFlags::empty())
+		.dst_access_mask(get_next_access_mask(barrier.next_accesses));
+
+	(
+		(src_stages, vk::PipelineStageFlags::BOTTOM_OF_PIPE, release),
+		(vk::PipelineStageFlags::TOP_OF_PIPE, dst_stages, acquire),
+	)
+}
+
+/// The acquire half of an ownership transfer has nothing to wait on locally, so
+/// every next access is made visible rather than only those following a write.
+fn get_next_access_mask(next_accesses: &[AccessType]) -> vk::AccessFlags {
+	next_accesses
+		.iter()
+		.fold(vk::AccessFlags::empty(), |mask, access| {
+			mask | get_access_info(*access).access_mask
+		})
+}
+
+/// Mapping function that translates a global barrier into a synchronization2
+/// memory barrier, that can be used with `vkCmdPipelineBarrier2` and the other
+/// synchronization2 commands.
+///
+/// Unlike `get_memory_barrier`, the source and destination stages are stored on
+/// the barrier itself. If no stages were determined they are left as `NONE`,
+/// which synchronization2 permits.
+pub fn get_memory_barrier2<'a>(barrier: &GlobalBarrier<'a>) -> vk::MemoryBarrier2<'a> {
+	let mut memory_barrier = vk::MemoryBarrier2::default();
+
+	for previous_access in barrier.previous_accesses {
+		let previous_info = get_access_info(*previous_access);
+
+		memory_barrier.src_stage_mask |= previous_info.stage_mask2;
+
+		// Add appropriate availability operations - for writes only.
+		if is_write_access(*previous_access) {
+			memory_barrier.src_access_mask |= previous_info.access_mask2;
+		}
+	}
+
+	for next_access in barrier.next_accesses {
+		let next_info = get_access_info(*next_access);
+
+		memory_barrier.dst_stage_mask |= next_info.stage_mask2;
+
+		// Add visibility operations as necessary.
+		// If the src access mask is empty, this is a WAR hazard (or for some reason a "RAR"),
+		// so the dst access mask can be safely zeroed as these don't need visibility.
+		if memory_barrier.src_access_mask != vk::AccessFlags2::empty() {
+			memory_barrier.dst_access_mask |= next_info.access_mask2;
+		}
+	}
+
+	memory_barrier
+}
+
+/// Mapping function that translates a buffer barrier into a synchronization2
+/// buffer memory barrier, with the source and destination stages stored on
+/// the barrier itself.
+pub fn get_buffer_memory_barrier2<'a>(barrier: &BufferBarrier<'a>) -> vk::BufferMemoryBarrier2<'a> {
+	let (src_queue_family_index, dst_queue_family_index) =
+		barrier.queue_transfer.queue_family_indices();
+
+	let mut buffer_barrier = vk::BufferMemoryBarrier2 {
+		src_queue_family_index,
+		dst_queue_family_index,
+		buffer: barrier.buffer,
+		offset: barrier.offset as u64,
+		size: barrier.size as u64,
+		..Default::default()
+	};
+
+	for previous_access in barrier.previous_accesses {
+		let previous_info = get_access_info(*previous_access);
+
+		buffer_barrier.src_stage_mask |= previous_info.stage_mask2;
+
+		// Add appropriate availability operations - for writes only.
+		if is_write_access(*previous_access) {
+			buffer_barrier.src_access_mask |= previous_info.access_mask2;
+		}
+	}
+
+	for next_access in barrier.next_accesses {
+		let next_info = get_access_info(*next_access);
+
+		buffer_barrier.dst_stage_mask |= next_info.stage_mask2;
+
+		// Add visibility operations as necessary.
+		if buffer_barrier.src_access_mask != vk::AccessFlags2::empty() {
+			buffer_barrier.dst_access_mask |= next_info.access_mask2;
+		}
+	}
+
+	buffer_barrier
+}
+
+/// Mapping function that translates an image barrier into a synchronization2
+/// image memory barrier, with the source and destination stages stored on
+/// the barrier itself.
+pub fn get_image_memory_barrier2<'a>(barrier: &ImageBarrier<'a>) -> vk::ImageMemoryBarrier2<'a> {
+	let (old_layout, new_layout) = get_unchecked_image_barrier_layouts(barrier);
+
+	let (src_queue_family_index, dst_queue_family_index) =
+		barrier.queue_transfer.queue_family_indices();
+
+	let mut image_barrier = vk::ImageMemoryBarrier2 {
+		src_queue_family_index,
+		dst_queue_family_index,
+		image: barrier.image,
+		subresource_range: barrier.range,
+		old_layout,
+		new_layout,
+		..Default::default()
+	};
+
+	for previous_access in barrier.previous_accesses {
+		let previous_info = get_access_info(*previous_access);
+
+		image_barrier.src_stage_mask |= previous_info.stage_mask2;
+
+		// Add appropriate availability operations - for writes only.
+		if is_write_access(*previous_access) {
+			image_barrier.src_access_mask |= previous_info.access_mask2;
+		}
+	}
+
+	for next_access in barrier.next_accesses {
+		let next_info = get_access_info(*next_access);
+
+		image_barrier.dst_stage_mask |= next_info.stage_mask2;
+
+		// Add visibility operations as necessary.
+		if image_barrier.src_access_mask != vk::AccessFlags2::empty() {
+			image_barrier.dst_access_mask |= next_info.access_mask2;
+		}
+	}
+
+	image_barrier
+}
+
+/// Synchronization2 version of `ownership_transfer_buffer`, returned as
+/// `(release, acquire)`.
+///
+/// The release half has no destination stages or accesses, and the acquire half
+/// no source stages or accesses - both are left as `NONE`.
+///
+/// # Panics
+///
+/// Panics in debug builds if the barrier doesn't define a queue family ownership
+/// transfer - see `QueueTransfer::is_transfer`.
+pub fn ownership_transfer_buffer2<'a>(
+	barrier: &BufferBarrier<'a>,
+) -> (vk::BufferMemoryBarrier2<'a>, vk::BufferMemoryBarrier2<'a>) {
+	debug_assert!(
+		barrier.queue_transfer.is_transfer(),
+		"Barrier doesn't define a queue family ownership transfer"
+	);
+	let buffer_barrier = get_buffer_memory_barrier2(barrier);
+
+	let release = buffer_barrier
+		.dst_stage_mask(vk::PipelineStageFlags2::NONE)
+		.dst_access_mask(vk::AccessFlags2::NONE);
+	let acquire = buffer_barrier
+		.src_stage_mask(vk::PipelineStageFlags2::NONE)
+		.src_access_mask(vk::AccessFlags2::NONE)
+		.dst_access_mask(get_next_access_mask2(barrier.next_accesses));
+
+	(release, acquire)
+}
+
+/// Synchronization2 version of `ownership_transfer_image` - see
+/// `ownership_transfer_buffer2`.
+///
+/// # Panics
+///
+/// Panics in debug builds if the barrier doesn't define a queue family ownership
+/// transfer - see `QueueTransfer::is_transfer`.
+pub fn ownership_transfer_image2<'a>(
+	barrier: &ImageBarrier<'a>,
+) -> (vk::ImageMemoryBarrier2<'a>, vk::ImageMemoryBarrier2<'a>) {
+	debug_assert!(
+		barrier.queue_transfer.is_transfer(),
+		"Barrier doesn't define a queue family ownership transfer"
+	);
+	let image_barrier = get_image_memory_barrier2(barrier);
+
+	let release = image_barrier
+		.dst_stage_mask(vk::PipelineStageFlags2::NONE)
+		.dst_access_mask(vk::AccessFlags2::NONE);
+	let acquire = image_barrier
+		.src_stage_mask(vk::PipelineStageFlags2::NONE)
+		.src_access_mask(vk::AccessFlags2::NONE)
+		.dst_access_mask(get_next_access_mask2(barrier.next_accesses));
+
+	(release, acquire)
+}
+
+/// Synchronization2 version of `get_next_access_mask`.
+fn get_next_access_mask2(next_accesses: &[AccessType]) -> vk::AccessFlags2 {
+	next_accesses
+		.iter()
+		.fold(vk::AccessFlags2::empty(), |mask, access| {
+			mask | get_access_info(*access).access_mask2
+		})
+}
+
+/// Mapping function that translates the accesses following a semaphore wait
+/// into the stages that have to wait on it - i.e. `pWaitDstStageMask` for
+/// `vkQueueSubmit`, or the stage mask of a `vk::SemaphoreSubmitInfo`.
+///
+/// If no stages were determined, both masks are `ALL_COMMANDS`, so that every
+/// subsequent command waits rather than none of them.
+pub fn semaphore_wait_stages(
+	next_accesses: &[AccessType],
+) -> (vk::PipelineStageFlags, vk::PipelineStageFlags2) {
+	get_semaphore_stage_masks(next_accesses)
+}
+
+/// Mapping function that translates the accesses preceding a semaphore signal
+/// into the stages that have to complete before it is signaled - i.e. the
+/// stage mask of a `vk::SemaphoreSubmitInfo`.
+///
+/// `vkQueueSubmit` always signals semaphores once all commands completed, so
+/// the legacy mask is only useful for other synchronization commands. If no
+/// stages were determined, both masks are `ALL_COMMANDS` - matching what
+/// `vkQueueSubmit` does.
+pub fn semaphore_signal_stages(
+	previous_accesses: &[AccessType],
+) -> (vk::PipelineStageFlags, vk::PipelineStageFlags2) {
+	get_semaphore_stage_masks(previous_accesses)
+}
+
+/// Builds the `vk::SemaphoreSubmitInfo` waiting on `semaphore` before
+/// `next_accesses` - see `semaphore_wait_stages`.
+///
+/// `value` is the value to wait for on a timeline semaphore, and is ignored for
+/// binary semaphores.
+pub fn semaphore_wait_info(
+	semaphore: vk::Semaphore,
+	value: u64,
+	next_accesses: &[AccessType],
+) -> vk::SemaphoreSubmitInfo<'static> {
+	let (_, stage_mask) = semaphore_wait_stages(next_accesses);
+
+	vk::SemaphoreSubmitInfo::default()
+		.semaphore(semaphore)
+		.value(value)
+		.stage_mask(stage_mask)
+}
+
+/// Builds the `vk::SemaphoreSubmitInfo` signaling `semaphore` after
+/// `previous_accesses` - see `semaphore_signal_stages`.
+///
+/// `value` is the value to signal on a timeline semaphore, and is ignored for
+/// binary semaphores.
+pub fn semaphore_signal_info(
+	semaphore: vk::Semaphore,
+	value: u64,
+	previous_accesses: &[AccessType],
+) -> vk::SemaphoreSubmitInfo<'static> {
+	let (_, stage_mask) = semaphore_signal_stages(previous_accesses);
+
+	vk::SemaphoreSubmitInfo::default()
+		.semaphore(semaphore)
+		.value(value)
+		.stage_mask(stage_mask)
+}
+
+fn get_stage_masks(accesses: &[AccessType]) -> (vk::PipelineStageFlags, vk::PipelineStageFlags2) {
+	accesses.iter().fold(
+		(
+			vk::PipelineStageFlags::empty(),
+			vk::PipelineStageFlags2::NONE,
+		),
+		|(stages, stages2), access| {
+			let info = get_access_info(*access);
+			(stages | info.stage_mask, stages2 | info.stage_mask2)
+		},
+	)
+}
+
+/// Version of `get_stage_masks` for semaphores, falling back to `ALL_COMMANDS`
+/// in both versions if no stages were determined.
+fn get_semaphore_stage_masks(
+	accesses: &[AccessType],
+) -> (vk::PipelineStageFlags, vk::PipelineStageFlags2) {
+	let (mut stages, mut stages2) = get_stage_masks(accesses);
+
+	if stages == vk::PipelineStageFlags::empty() {
+		stages = vk::PipelineStageFlags::ALL_COMMANDS;
+	}
+	if stages2 == vk::PipelineStageFlags2::NONE {
+		stages2 = vk::PipelineStageFlags2::ALL_COMMANDS;
+	}
+
+	(stages, stages2)
+}
+
+/// Version of `is_image_barrier_required` for a barrier that was already mapped,
+/// so that its layouts don't have to be resolved again.
+pub(crate) fn is_mapped_image_barrier_required(
+	barrier: &ImageBarrier,
+	old_layout: vk::ImageLayout,
+	new_layout: vk::ImageLayout,
+) -> bool {
+	old_layout != new_layout
+		|| barrier.queue_transfer.is_transfer()
+		|| is_hazard(barrier.previous_accesses, barrier.next_accesses)
+}
+
+/// Returns true if there are accesses on both sides, and at least one of them writes.
+fn is_hazard(previous_accesses: &[AccessType], next_accesses: &[AccessType]) -> bool {
+	let accessed =
+		|accesses: &[AccessType]| accesses.iter().any(|access| *access != AccessType::Nothing);
+	let writes = |accesses: &[AccessType]| accesses.iter().any(|access| is_write_access(*access));
+
+	accessed(previous_accesses)
+		&& accessed(next_accesses)
+		&& (writes(previous_accesses) || writes(next_accesses))
+}
+
+/// Checks that a set of accesses contains either a single write, or any number
+/// of reads. `AccessType::Nothing` is ignored.
+pub(crate) fn validate_accesses(accesses: &[AccessType]) -> Result<(), BarrierError> {
+	let mut write = None;
+	let mut read = None;
+
+	for access in accesses.iter().copied() {
+		if access == AccessType::Nothing {
+			continue;
+		}
+
+		if is_write_access(access) {
+			if let Some(first) = write {
+				return Err(BarrierError::MultipleWriteAccesses {
+					first,
+					second: access,
+				});
+			}
+			write = Some(access);
+		} else if read.is_none() {
+			read = Some(access);
+		}
+	}
+
+	match (write, read) {
+		(Some(write), Some(read)) => Err(BarrierError::WriteMixedWithReads { write, read }),
+		_ => Ok(()),
+	}
+}
+
+pub(crate) fn validate_global_barrier(barrier: &GlobalBarrier) -> Result<(), BarrierError> {
+	validate_accesses(barrier.previous_accesses)?;
+	validate_accesses(barrier.next_accesses)?;
+
+	let mut accesses = barrier
+		.previous_accesses
+		.iter()
+		.chain(barrier.next_accesses);
+	if accesses.any(|access| *access == AccessType::Present) {
+		return Err(BarrierError::PresentInGlobalBarrier);
+	}
+
+	Ok(())
+}
+
+pub(crate) fn validate_buffer_barrier(barrier: &BufferBarrier) -> Result<(), BarrierError> {
+	validate_accesses(barrier.previous_accesses)?;
+	validate_accesses(barrier.next_accesses)?;
+
+	let mut accesses = barrier
+		.previous_accesses
+		.iter()
+		.chain(barrier.next_accesses);
+	if let Some(access) = accesses.find(|access| is_image_only_access(**access)) {
+		return Err(BarrierError::ImageOnlyAccessOnBuffer(*access));
+	}
+
+	Ok(())
+}
+
+/// Validates an image barrier, and resolves the old and new native layouts.
+pub(crate) fn get_image_barrier_layouts(
+	barrier: &ImageBarrier,
+) -> Result<(vk::ImageLayout, vk::ImageLayout), BarrierError> {
+	validate_accesses(barrier.previous_accesses)?;
+	validate_accesses(barrier.next_accesses)?;
+
+	let old_layout = if barrier.discard_contents {
+		vk::ImageLayout::UNDEFINED
+	} else {
+		resolve_image_layout(barrier.previous_layout, barrier.previous_accesses)?
+	};
+	let new_layout = resolve_image_layout(barrier.next_layout, barrier.next_accesses)?;
+
+	Ok((old_layout, new_layout))
+}
+
+/// Resolves the old and new native layouts of an image barrier without
+/// validating it - if the accesses on one side imply different layouts, the
+/// last one wins.
+fn get_unchecked_image_barrier_layouts(
+	barrier: &ImageBarrier,
+) -> (vk::ImageLayout, vk::ImageLayout) {
+	let last_layout = |layout: ImageLayout, accesses: &[AccessType]| {
+		accesses
+			.iter()
+			.rev()
+			.find(|access| **access != AccessType::Nothing)
+			.or(accesses.first())
+			.map_or(vk::ImageLayout::UNDEFINED, |access| {
+				get_image_layout(layout, *access)
+			})
+	};
+
+	let old_layout = if barrier.discard_contents {
+		vk::ImageLayout::UNDEFINED
+	} else {
+		last_layout(barrier.previous_layout, barrier.previous_accesses)
+	};
+	let new_layout = last_layout(barrier.next_layout, barrier.next_accesses);
+
+	(old_layout, new_layout)
+}
+
+/// Resolves the single native image layout implied by a set of accesses.
+/// `AccessType::Nothing` only determines the layout if it's the sole access.
+pub(crate) fn resolve_image_layout(
+	layout: ImageLayout,
+	accesses: &[AccessType],
+) -> Result<vk::ImageLayout, BarrierError> {
+	let mut resolved = None;
+
+	for access in accesses.iter().copied() {
+		if access == AccessType::Nothing {
+			continue;
+		}
+
+		let access_layout = get_image_layout(layout, access);
+		match resolved {
+			Some(first) if first != access_layout => {
+				return Err(BarrierError::ConflictingLayouts {
+					first,
+					second: access_layout,
+				});
+			}
+			_ => resolved = Some(access_layout),
+		}
+	}
+
+	Ok(match resolved {
+		Some(resolved) => resolved,
+		None if accesses.is_empty() => vk::ImageLayout::UNDEFINED,
+		None => get_image_layout(layout, AccessType::Nothing),
+	})
+}
+
+/// Resolves the native image layout used for an access, given one of the
+/// simplified layout options.
+pub(crate) fn get_image_layout(layout: ImageLayout, access_type: AccessType) -> vk::ImageLayout {
+	match layout {
+		ImageLayout::General => {
+			if access_type == AccessType::Present {
+				vk::ImageLayout::PRESENT_SRC_KHR
+			} else {
+				vk::ImageLayout::GENERAL
+			}
+		}
+		ImageLayout::Optimal => get_access_info(access_type).image_layout,
+		// Shared presentable images stay in the one layout for every access, including
+		// `AccessType::Present` - the presentation engine reads them in place.
+		ImageLayout::GeneralAndPresentation => vk::ImageLayout::SHARED_PRESENT_KHR,
+	}
 }
 
 pub(crate) struct AccessInfo {
 	pub(crate) stage_mask: vk::PipelineStageFlags,
 	pub(crate) access_mask: vk::AccessFlags,
 	pub(crate) image_layout: vk::ImageLayout,
+	pub(crate) stage_mask2: vk::PipelineStageFlags2,
+	pub(crate) access_mask2: vk::AccessFlags2,
 }
 
 pub(crate) fn get_access_info(access_type: AccessType) -> AccessInfo {
@@ -509,215 +1479,301 @@ pub(crate) fn get_access_info(access_type: AccessType) -> AccessInfo {
 			stage_mask: vk::PipelineStageFlags::empty(),
 			access_mask: vk::AccessFlags::empty(),
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::NONE,
+			access_mask2: vk::AccessFlags2::NONE,
 		},
 		AccessType::CommandBufferReadNVX => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COMMAND_PREPROCESS_NV,
 			access_mask: vk::AccessFlags::COMMAND_PREPROCESS_READ_NV,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::COMMAND_PREPROCESS_NV,
+			access_mask2: vk::AccessFlags2::COMMAND_PREPROCESS_READ_NV,
 		},
 		AccessType::IndirectBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::DRAW_INDIRECT,
 			access_mask: vk::AccessFlags::INDIRECT_COMMAND_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::DRAW_INDIRECT,
+			access_mask2: vk::AccessFlags2::INDIRECT_COMMAND_READ,
 		},
 		AccessType::IndexBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::VERTEX_INPUT,
 			access_mask: vk::AccessFlags::INDEX_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::INDEX_INPUT,
+			access_mask2: vk::AccessFlags2::INDEX_READ,
 		},
 		AccessType::VertexBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::VERTEX_INPUT,
 			access_mask: vk::AccessFlags::VERTEX_ATTRIBUTE_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT,
+			access_mask2: vk::AccessFlags2::VERTEX_ATTRIBUTE_READ,
 		},
 		AccessType::VertexShaderReadUniformBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::VERTEX_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::VERTEX_SHADER,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
 		},
 		AccessType::VertexShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::VERTEX_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::VERTEX_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
 		},
 		AccessType::VertexShaderReadOther => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::VERTEX_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::VERTEX_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
 		},
 		AccessType::TessellationControlShaderReadUniformBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TESSELLATION_CONTROL_SHADER,
 			access_mask: vk::AccessFlags::UNIFORM_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::TESSELLATION_CONTROL_SHADER,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
 		},
 		AccessType::TessellationControlShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TESSELLATION_CONTROL_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::TESSELLATION_CONTROL_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
 		},
 		AccessType::TessellationControlShaderReadOther => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TESSELLATION_CONTROL_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::TESSELLATION_CONTROL_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
 		},
 		AccessType::TessellationEvaluationShaderReadUniformBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TESSELLATION_EVALUATION_SHADER,
 			access_mask: vk::AccessFlags::UNIFORM_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::TESSELLATION_EVALUATION_SHADER,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
 		},
 		AccessType::TessellationEvaluationShaderReadSampledImageOrUniformTexelBuffer => {
 			AccessInfo {
 				stage_mask: vk::PipelineStageFlags::TESSELLATION_EVALUATION_SHADER,
 				access_mask: vk::AccessFlags::SHADER_READ,
 				image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+				stage_mask2: vk::PipelineStageFlags2::TESSELLATION_EVALUATION_SHADER,
+				access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
 			}
 		}
 		AccessType::TessellationEvaluationShaderReadOther => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TESSELLATION_EVALUATION_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::TESSELLATION_EVALUATION_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
 		},
 		AccessType::GeometryShaderReadUniformBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::GEOMETRY_SHADER,
 			access_mask: vk::AccessFlags::UNIFORM_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::GEOMETRY_SHADER,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
 		},
 		AccessType::GeometryShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::GEOMETRY_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::GEOMETRY_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
 		},
 		AccessType::GeometryShaderReadOther => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::GEOMETRY_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::GEOMETRY_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
 		},
 		AccessType::FragmentShaderReadUniformBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::FRAGMENT_SHADER,
 			access_mask: vk::AccessFlags::UNIFORM_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::FRAGMENT_SHADER,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
 		},
 		AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::FRAGMENT_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::FRAGMENT_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
 		},
 		AccessType::FragmentShaderReadColorInputAttachment => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::FRAGMENT_SHADER,
 			access_mask: vk::AccessFlags::INPUT_ATTACHMENT_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::FRAGMENT_SHADER,
+			access_mask2: vk::AccessFlags2::INPUT_ATTACHMENT_READ,
 		},
 		AccessType::FragmentShaderReadDepthStencilInputAttachment => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::FRAGMENT_SHADER,
 			access_mask: vk::AccessFlags::INPUT_ATTACHMENT_READ,
 			image_layout: vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::FRAGMENT_SHADER,
+			access_mask2: vk::AccessFlags2::INPUT_ATTACHMENT_READ,
 		},
 		AccessType::FragmentShaderReadOther => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::FRAGMENT_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::FRAGMENT_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
 		},
 		AccessType::ColorAttachmentRead => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
 			access_mask: vk::AccessFlags::COLOR_ATTACHMENT_READ,
 			image_layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+			access_mask2: vk::AccessFlags2::COLOR_ATTACHMENT_READ,
 		},
 		AccessType::DepthStencilAttachmentRead => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS
 				| vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
 			access_mask: vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ,
 			image_layout: vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+				| vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+			access_mask2: vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ,
 		},
 		AccessType::ComputeShaderReadUniformBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COMPUTE_SHADER,
 			access_mask: vk::AccessFlags::UNIFORM_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::COMPUTE_SHADER,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
 		},
 		AccessType::ComputeShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COMPUTE_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::COMPUTE_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
 		},
 		AccessType::ComputeShaderReadOther => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COMPUTE_SHADER,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::COMPUTE_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
 		},
 		AccessType::AnyShaderReadUniformBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ALL_COMMANDS,
 			access_mask: vk::AccessFlags::UNIFORM_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ALL_COMMANDS,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
 		},
 		AccessType::AnyShaderReadUniformBufferOrVertexBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ALL_COMMANDS,
 			access_mask: vk::AccessFlags::UNIFORM_READ | vk::AccessFlags::VERTEX_ATTRIBUTE_READ,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ALL_COMMANDS,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ | vk::AccessFlags2::VERTEX_ATTRIBUTE_READ,
 		},
 		AccessType::AnyShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ALL_COMMANDS,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::ALL_COMMANDS,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
 		},
 		AccessType::AnyShaderReadOther => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ALL_COMMANDS,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::ALL_COMMANDS,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
 		},
 		AccessType::TransferRead => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TRANSFER,
 			access_mask: vk::AccessFlags::TRANSFER_READ,
 			image_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::ALL_TRANSFER,
+			access_mask2: vk::AccessFlags2::TRANSFER_READ,
 		},
 		AccessType::HostRead => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::HOST,
 			access_mask: vk::AccessFlags::HOST_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::HOST,
+			access_mask2: vk::AccessFlags2::HOST_READ,
 		},
 		AccessType::Present => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::empty(),
 			access_mask: vk::AccessFlags::empty(),
 			image_layout: vk::ImageLayout::PRESENT_SRC_KHR,
+			stage_mask2: vk::PipelineStageFlags2::NONE,
+			access_mask2: vk::AccessFlags2::NONE,
 		},
 		AccessType::CommandBufferWriteNVX => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COMMAND_PREPROCESS_NV,
 			access_mask: vk::AccessFlags::COMMAND_PREPROCESS_WRITE_NV,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::COMMAND_PREPROCESS_NV,
+			access_mask2: vk::AccessFlags2::COMMAND_PREPROCESS_WRITE_NV,
 		},
 		AccessType::VertexShaderWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::VERTEX_SHADER,
 			access_mask: vk::AccessFlags::SHADER_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::VERTEX_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
 		},
 		AccessType::TessellationControlShaderWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TESSELLATION_CONTROL_SHADER,
 			access_mask: vk::AccessFlags::SHADER_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::TESSELLATION_CONTROL_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
 		},
 		AccessType::TessellationEvaluationShaderWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TESSELLATION_EVALUATION_SHADER,
 			access_mask: vk::AccessFlags::SHADER_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::TESSELLATION_EVALUATION_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
 		},
 		AccessType::GeometryShaderWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::GEOMETRY_SHADER,
 			access_mask: vk::AccessFlags::SHADER_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::GEOMETRY_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
 		},
 		AccessType::FragmentShaderWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::FRAGMENT_SHADER,
 			access_mask: vk::AccessFlags::SHADER_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::FRAGMENT_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
 		},
 		AccessType::ColorAttachmentWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
 			access_mask: vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
 			image_layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+			access_mask2: vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
 		},
 		AccessType::DepthStencilAttachmentWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS
 				| vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
 			access_mask: vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
 			image_layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+				| vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+			access_mask2: vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
 		},
 		AccessType::DepthAttachmentWriteStencilReadOnly => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS
@@ -725,6 +1781,10 @@ pub(crate) fn get_access_info(access_type: AccessType) -> AccessInfo {
 			access_mask: vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE
 				| vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ,
 			image_layout: vk::ImageLayout::DEPTH_ATTACHMENT_STENCIL_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+				| vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+			access_mask2: vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE
+				| vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ,
 		},
 		AccessType::StencilAttachmentWriteDepthReadOnly => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS
@@ -732,81 +1792,305 @@ pub(crate) fn get_access_info(access_type: AccessType) -> AccessInfo {
 			access_mask: vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE
 				| vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ,
 			image_layout: vk::ImageLayout::DEPTH_READ_ONLY_STENCIL_ATTACHMENT_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+				| vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+			access_mask2: vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE
+				| vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ,
 		},
 		AccessType::ComputeShaderWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COMPUTE_SHADER,
 			access_mask: vk::AccessFlags::SHADER_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::COMPUTE_SHADER,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
 		},
 		AccessType::AnyShaderWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ALL_COMMANDS,
 			access_mask: vk::AccessFlags::SHADER_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::ALL_COMMANDS,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
 		},
 		AccessType::TransferWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::TRANSFER,
 			access_mask: vk::AccessFlags::TRANSFER_WRITE,
 			image_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::ALL_TRANSFER,
+			access_mask2: vk::AccessFlags2::TRANSFER_WRITE,
 		},
 		AccessType::HostWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::HOST,
 			access_mask: vk::AccessFlags::HOST_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::HOST,
+			access_mask2: vk::AccessFlags2::HOST_WRITE,
 		},
 		AccessType::ColorAttachmentReadWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
 			access_mask: vk::AccessFlags::COLOR_ATTACHMENT_READ
 				| vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
 			image_layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+			access_mask2: vk::AccessFlags2::COLOR_ATTACHMENT_READ
+				| vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
 		},
 		AccessType::General => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ALL_COMMANDS,
 			access_mask: vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::ALL_COMMANDS,
+			access_mask2: vk::AccessFlags2::MEMORY_READ | vk::AccessFlags2::MEMORY_WRITE,
 		},
 		AccessType::RayTracingShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
 		},
 		AccessType::RayTracingShaderReadColorInputAttachment => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
 			access_mask: vk::AccessFlags::INPUT_ATTACHMENT_READ,
 			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR,
+			access_mask2: vk::AccessFlags2::INPUT_ATTACHMENT_READ,
 		},
 		AccessType::RayTracingShaderReadDepthStencilInputAttachment => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
 			access_mask: vk::AccessFlags::INPUT_ATTACHMENT_READ,
 			image_layout: vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR,
+			access_mask2: vk::AccessFlags2::INPUT_ATTACHMENT_READ,
 		},
 		AccessType::RayTracingShaderReadAccelerationStructure => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
 			access_mask: vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR,
+			access_mask2: vk::AccessFlags2::ACCELERATION_STRUCTURE_READ_KHR,
 		},
 		AccessType::RayTracingShaderReadOther => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
 			access_mask: vk::AccessFlags::SHADER_READ,
 			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
 		},
 		AccessType::AccelerationStructureBuildWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
 			access_mask: vk::AccessFlags::ACCELERATION_STRUCTURE_WRITE_KHR,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask2: vk::AccessFlags2::ACCELERATION_STRUCTURE_WRITE_KHR,
 		},
 		AccessType::AccelerationStructureBuildRead => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
 			access_mask: vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask2: vk::AccessFlags2::ACCELERATION_STRUCTURE_READ_KHR,
 		},
 		AccessType::AccelerationStructureBufferWrite => AccessInfo {
 			stage_mask: vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
 			access_mask: vk::AccessFlags::TRANSFER_WRITE,
 			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask2: vk::AccessFlags2::TRANSFER_WRITE,
+		},
+		AccessType::CopyRead => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TRANSFER,
+			access_mask: vk::AccessFlags::TRANSFER_READ,
+			image_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::COPY,
+			access_mask2: vk::AccessFlags2::TRANSFER_READ,
+		},
+		AccessType::CopyWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TRANSFER,
+			access_mask: vk::AccessFlags::TRANSFER_WRITE,
+			image_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::COPY,
+			access_mask2: vk::AccessFlags2::TRANSFER_WRITE,
+		},
+		AccessType::BlitRead => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TRANSFER,
+			access_mask: vk::AccessFlags::TRANSFER_READ,
+			image_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::BLIT,
+			access_mask2: vk::AccessFlags2::TRANSFER_READ,
+		},
+		AccessType::BlitWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TRANSFER,
+			access_mask: vk::AccessFlags::TRANSFER_WRITE,
+			image_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::BLIT,
+			access_mask2: vk::AccessFlags2::TRANSFER_WRITE,
+		},
+		AccessType::ResolveRead => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TRANSFER,
+			access_mask: vk::AccessFlags::TRANSFER_READ,
+			image_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::RESOLVE,
+			access_mask2: vk::AccessFlags2::TRANSFER_READ,
+		},
+		AccessType::ResolveWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TRANSFER,
+			access_mask: vk::AccessFlags::TRANSFER_WRITE,
+			image_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::RESOLVE,
+			access_mask2: vk::AccessFlags2::TRANSFER_WRITE,
+		},
+		AccessType::ClearWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TRANSFER,
+			access_mask: vk::AccessFlags::TRANSFER_WRITE,
+			image_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::CLEAR,
+			access_mask2: vk::AccessFlags2::TRANSFER_WRITE,
+		},
+		AccessType::TaskShaderReadUniformBuffer => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TASK_SHADER_EXT,
+			access_mask: vk::AccessFlags::UNIFORM_READ,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::TASK_SHADER_EXT,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
+		},
+		AccessType::TaskShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TASK_SHADER_EXT,
+			access_mask: vk::AccessFlags::SHADER_READ,
+			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::TASK_SHADER_EXT,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
+		},
+		AccessType::TaskShaderReadOther => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TASK_SHADER_EXT,
+			access_mask: vk::AccessFlags::SHADER_READ,
+			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::TASK_SHADER_EXT,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
+		},
+		AccessType::TaskShaderWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::TASK_SHADER_EXT,
+			access_mask: vk::AccessFlags::SHADER_WRITE,
+			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::TASK_SHADER_EXT,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
+		},
+		AccessType::MeshShaderReadUniformBuffer => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::MESH_SHADER_EXT,
+			access_mask: vk::AccessFlags::UNIFORM_READ,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::MESH_SHADER_EXT,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
+		},
+		AccessType::MeshShaderReadSampledImageOrUniformTexelBuffer => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::MESH_SHADER_EXT,
+			access_mask: vk::AccessFlags::SHADER_READ,
+			image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::MESH_SHADER_EXT,
+			access_mask2: vk::AccessFlags2::SHADER_SAMPLED_READ,
+		},
+		AccessType::MeshShaderReadOther => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::MESH_SHADER_EXT,
+			access_mask: vk::AccessFlags::SHADER_READ,
+			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::MESH_SHADER_EXT,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_READ,
+		},
+		AccessType::MeshShaderWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::MESH_SHADER_EXT,
+			access_mask: vk::AccessFlags::SHADER_WRITE,
+			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::MESH_SHADER_EXT,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
+		},
+		AccessType::RayTracingShaderReadUniformBuffer => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
+			access_mask: vk::AccessFlags::UNIFORM_READ,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR,
+			access_mask2: vk::AccessFlags2::UNIFORM_READ,
+		},
+		AccessType::RayTracingShaderWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
+			access_mask: vk::AccessFlags::SHADER_WRITE,
+			image_layout: vk::ImageLayout::GENERAL,
+			stage_mask2: vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR,
+			access_mask2: vk::AccessFlags2::SHADER_STORAGE_WRITE,
+		},
+		AccessType::ShaderBindingTableRead => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
+			access_mask: vk::AccessFlags::SHADER_READ,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR,
+			access_mask2: vk::AccessFlags2::SHADER_BINDING_TABLE_READ_KHR,
+		},
+		AccessType::AccelerationStructureBuildInputRead => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask: vk::AccessFlags::SHADER_READ,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask2: vk::AccessFlags2::SHADER_READ,
+		},
+		AccessType::AccelerationStructureCopyRead => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask: vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_COPY_KHR,
+			access_mask2: vk::AccessFlags2::ACCELERATION_STRUCTURE_READ_KHR,
+		},
+		AccessType::AccelerationStructureCopyWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask: vk::AccessFlags::ACCELERATION_STRUCTURE_WRITE_KHR,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_COPY_KHR,
+			access_mask2: vk::AccessFlags2::ACCELERATION_STRUCTURE_WRITE_KHR,
+		},
+		AccessType::AccelerationStructureSerializeWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask: vk::AccessFlags::TRANSFER_WRITE,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_COPY_KHR,
+			access_mask2: vk::AccessFlags2::TRANSFER_WRITE,
+		},
+		AccessType::AccelerationStructureDeserializeRead => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
+			access_mask: vk::AccessFlags::TRANSFER_READ,
+			image_layout: vk::ImageLayout::UNDEFINED,
+			stage_mask2: vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_COPY_KHR,
+			access_mask2: vk::AccessFlags2::TRANSFER_READ,
+		},
+		AccessType::DepthStencilResolveWrite => AccessInfo {
+			stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+			access_mask: vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+			image_layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+			stage_mask2: vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+			access_mask2: vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
 		},
 	}
 }
 
+pub(crate) fn is_image_only_access(access_type: AccessType) -> bool {
+	matches!(
+		access_type,
+		AccessType::FragmentShaderReadColorInputAttachment
+			| AccessType::FragmentShaderReadDepthStencilInputAttachment
+			| AccessType::ColorAttachmentRead
+			| AccessType::DepthStencilAttachmentRead
+			| AccessType::Present
+			| AccessType::ColorAttachmentWrite
+			| AccessType::DepthStencilAttachmentWrite
+			| AccessType::DepthAttachmentWriteStencilReadOnly
+			| AccessType::StencilAttachmentWriteDepthReadOnly
+			| AccessType::ColorAttachmentReadWrite
+			| AccessType::RayTracingShaderReadColorInputAttachment
+			| AccessType::RayTracingShaderReadDepthStencilInputAttachment
+			| AccessType::BlitRead
+			| AccessType::BlitWrite
+			| AccessType::ResolveRead
+			| AccessType::ResolveWrite
+			| AccessType::DepthStencilResolveWrite
+	)
+}
+
 pub(crate) fn is_write_access(access_type: AccessType) -> bool {
 	matches!(
 		access_type,
@@ -826,5 +2110,17 @@ pub(crate) fn is_write_access(access_type: AccessType) -> bool {
 			| AccessType::HostWrite
 			| AccessType::ColorAttachmentReadWrite
 			| AccessType::General
+			| AccessType::AccelerationStructureBuildWrite
+			| AccessType::AccelerationStructureBufferWrite
+			| AccessType::RayTracingShaderWrite
+			| AccessType::AccelerationStructureCopyWrite
+			| AccessType::AccelerationStructureSerializeWrite
+			| AccessType::CopyWrite
+			| AccessType::BlitWrite
+			| AccessType::ResolveWrite
+			| AccessType::ClearWrite
+			| AccessType::TaskShaderWrite
+			| AccessType::MeshShaderWrite
+			| AccessType::DepthStencilResolveWrite
 	)
 }
@@ -0,0 +1,112 @@
+//! Render pass helpers, deriving subpass dependencies and attachment layouts
+//! from the same access types used for barriers.
+//!
+//! Only the synchronization related members are filled in - formats, sample
+//! counts and load/store operations are left at their defaults, to be set with
+//! the usual builder methods.
+
+use super::*;
+
+/// Mapping function that translates a global barrier between two subpasses into
+/// a subpass dependency, with the same stage and access masks as
+/// `get_memory_barrier`.
+///
+/// `src_subpass` and `dst_subpass` are passed unmodified, so either can be
+/// `vk::SUBPASS_EXTERNAL`. No dependency flags are set - framebuffer-local
+/// dependencies between subpasses will usually want `BY_REGION` added with
+/// `.dependency_flags()`.
+pub fn get_subpass_dependency(
+	src_subpass: u32,
+	dst_subpass: u32,
+	barrier: &GlobalBarrier,
+) -> vk::SubpassDependency {
+	let (src_stage_mask, dst_stage_mask, memory_barrier) = get_memory_barrier(barrier);
+
+	vk::SubpassDependency {
+		src_subpass,
+		dst_subpass,
+		src_stage_mask,
+		dst_stage_mask,
+		src_access_mask: memory_barrier.src_access_mask,
+		dst_access_mask: memory_barrier.dst_access_mask,
+		dependency_flags: vk::DependencyFlags::empty(),
+	}
+}
+
+/// Mapping function that translates a global barrier between two subpasses into
+/// a `vk::SubpassDependency2`, for `vkCreateRenderPass2` - see
+/// `get_subpass_dependency`.
+pub fn get_subpass_dependency2(
+	src_subpass: u32,
+	dst_subpass: u32,
+	barrier: &GlobalBarrier,
+) -> vk::SubpassDependency2<'static> {
+	let dependency = get_subpass_dependency(src_subpass, dst_subpass, barrier);
+
+	vk::SubpassDependency2::default()
+		.src_subpass(dependency.src_subpass)
+		.dst_subpass(dependency.dst_subpass)
+		.src_stage_mask(dependency.src_stage_mask)
+		.dst_stage_mask(dependency.dst_stage_mask)
+		.src_access_mask(dependency.src_access_mask)
+		.dst_access_mask(dependency.dst_access_mask)
+}
+
+/// Builds an attachment description whose initial layout matches the accesses
+/// before the render pass, and whose final layout matches the accesses after it.
+///
+/// If `discard_contents` is set the initial layout is `UNDEFINED`, which should
+/// be paired with a `DONT_CARE` or `CLEAR` load operation.
+///
+/// # Panics
+///
+/// Panics if either set of accesses doesn't resolve to a single image layout -
+/// see `try_get_image_memory_barrier`. Also panics if there are no next accesses
+/// (or only `AccessType::Nothing`), as the final layout can't be `UNDEFINED`.
+pub fn get_attachment_description(
+	previous_accesses: &[AccessType],
+	previous_layout: ImageLayout,
+	next_accesses: &[AccessType],
+	next_layout: ImageLayout,
+	discard_contents: bool,
+) -> vk::AttachmentDescription {
+	let (initial_layout, final_layout) = get_image_barrier_layouts(&ImageBarrier {
+		previous_accesses,
+		next_accesses,
+		previous_layout,
+		next_layout,
+		discard_contents,
+		..Default::default()
+	})
+	.unwrap_or_else(|error| panic!("Invalid attachment description: {}", error));
+	assert!(
+		final_layout != vk::ImageLayout::UNDEFINED,
+		"Invalid attachment description: the final layout can't be UNDEFINED"
+	);
+
+	vk::AttachmentDescription::default()
+		.initial_layout(initial_layout)
+		.final_layout(final_layout)
+}
+
+/// Builds the reference to `attachment` used by a subpass accessing it with
+/// `accesses`, in the layout implied by them.
+///
+/// Unlike barriers, a subpass can both write and read an attachment, e.g. a
+/// feedback loop through an input attachment. The write and the read imply
+/// different optimal layouts, so feedback loops require `ImageLayout::General`.
+///
+/// # Panics
+///
+/// Panics if the accesses don't resolve to a single image layout - see
+/// `try_get_image_memory_barrier`.
+pub fn get_attachment_reference(
+	attachment: u32,
+	accesses: &[AccessType],
+	layout: ImageLayout,
+) -> vk::AttachmentReference {
+	let layout = resolve_image_layout(layout, accesses)
+		.unwrap_or_else(|error| panic!("Invalid attachment reference: {}", error));
+
+	vk::AttachmentReference { attachment, layout }
+}
@@ -0,0 +1,233 @@
+//! Dynamic rendering helpers, transitioning attachments around
+//! `vkCmdBeginRendering`/`vkCmdEndRendering` and picking the layouts of each
+//! `vk::RenderingAttachmentInfo` from the same access types.
+
+use super::*;
+use ash;
+
+/// An attachment used with dynamic rendering, along with how it was last
+/// accessed and how the rendering will access it.
+///
+/// `next_accesses` are the accesses during rendering, e.g.
+/// `AccessType::ColorAttachmentWrite` or `AccessType::DepthStencilAttachmentRead`,
+/// and determine the image layout of the attachment.
+#[derive(Debug, Default, Clone)]
+pub struct RenderingAttachment<'a> {
+	pub image_view: vk::ImageView,
+	pub image: vk::Image,
+	pub range: vk::ImageSubresourceRange,
+	pub previous_accesses: &'a [AccessType],
+	pub previous_layout: ImageLayout,
+	pub next_accesses: &'a [AccessType],
+	pub next_layout: ImageLayout,
+	pub discard_contents: bool,
+	pub resolve: Option<ResolveAttachment<'a>>,
+}
+
+/// The attachment a multisampled attachment is resolved into.
+///
+/// Resolves write the whole render area, so `discard_contents` can usually be
+/// set if the render area covers the image. Color resolves are written as
+/// `AccessType::ColorAttachmentWrite`, and depth/stencil resolves (when `range`
+/// has a depth or stencil aspect) as `AccessType::DepthStencilResolveWrite`.
+#[derive(Debug, Default, Clone)]
+pub struct ResolveAttachment<'a> {
+	pub image_view: vk::ImageView,
+	pub image: vk::Image,
+	pub range: vk::ImageSubresourceRange,
+	pub mode: vk::ResolveModeFlags,
+	pub previous_accesses: &'a [AccessType],
+	pub previous_layout: ImageLayout,
+	pub layout: ImageLayout,
+	pub discard_contents: bool,
+}
+
+/// How an attachment and its resolve attachment are accessed after rendering -
+/// see `end_rendering_barriers`.
+///
+/// Empty accesses leave the image as it is after rendering, e.g. for a
+/// transient multisampled attachment that is only resolved.
+#[derive(Debug, Default, Clone)]
+pub struct RenderingAttachmentEnd<'a> {
+	pub next_accesses: &'a [AccessType],
+	pub next_layout: ImageLayout,
+	pub resolve_next_accesses: &'a [AccessType],
+	pub resolve_next_layout: ImageLayout,
+}
+
+impl<'a> RenderingAttachment<'a> {
+	/// Returns the barrier transitioning the attachment before rendering.
+	pub fn begin_barrier(&self) -> ImageBarrier<'a> {
+		ImageBarrier {
+			previous_accesses: self.previous_accesses,
+			next_accesses: self.next_accesses,
+			previous_layout: self.previous_layout,
+			next_layout: self.next_layout,
+			discard_contents: self.discard_contents,
+			queue_transfer: QueueTransfer::None,
+			image: self.image,
+			range: self.range,
+		}
+	}
+
+	/// Returns the barrier transitioning the attachment after rendering, before
+	/// it is accessed by `next_accesses` in `next_layout`.
+	pub fn end_barrier(
+		&self,
+		next_accesses: &'a [AccessType],
+		next_layout: ImageLayout,
+	) -> ImageBarrier<'a> {
+		ImageBarrier {
+			previous_accesses: self.next_accesses,
+			next_accesses,
+			previous_layout: self.next_layout,
+			next_layout,
+			discard_contents: false,
+			queue_transfer: QueueTransfer::None,
+			image: self.image,
+			range: self.range,
+		}
+	}
+
+	/// Returns the rendering attachment info, with the image view and layout of
+	/// the attachment and its resolve attachment set. Load and store operations
+	/// and the clear value are left at their defaults.
+	///
+	/// # Panics
+	///
+	/// Panics if the accesses don't resolve to a single image layout - see
+	/// `try_get_image_memory_barrier`.
+	pub fn attachment_info(&self) -> vk::RenderingAttachmentInfo<'static> {
+		let image_layout = resolve_image_layout(self.next_layout, self.next_accesses)
+			.unwrap_or_else(|error| panic!("Invalid rendering attachment: {}", error));
+
+		let info = vk::RenderingAttachmentInfo::default()
+			.image_view(self.image_view)
+			.image_layout(image_layout);
+
+		match &self.resolve {
+			Some(resolve) => info
+				.resolve_mode(resolve.mode)
+				.resolve_image_view(resolve.image_view)
+				.resolve_image_layout(get_image_layout(resolve.layout, resolve.next_access())),
+			None => info,
+		}
+	}
+}
+
+impl<'a> ResolveAttachment<'a> {
+	/// Returns the barrier transitioning the resolve attachment before rendering.
+	pub fn begin_barrier(&self) -> ImageBarrier<'a> {
+		ImageBarrier {
+			previous_accesses: self.previous_accesses,
+			next_accesses: self.next_accesses(),
+			previous_layout: self.previous_layout,
+			next_layout: self.layout,
+			discard_contents: self.discard_contents,
+			queue_transfer: QueueTransfer::None,
+			image: self.image,
+			range: self.range,
+		}
+	}
+
+	/// Returns the barrier transitioning the resolve attachment after rendering,
+	/// before it is accessed by `next_accesses` in `next_layout`.
+	pub fn end_barrier(
+		&self,
+		next_accesses: &'a [AccessType],
+		next_layout: ImageLayout,
+	) -> ImageBarrier<'a> {
+		ImageBarrier {
+			previous_accesses: self.next_accesses(),
+			next_accesses,
+			previous_layout: self.layout,
+			next_layout,
+			discard_contents: false,
+			queue_transfer: QueueTransfer::None,
+			image: self.image,
+			range: self.range,
+		}
+	}
+
+	fn next_access(&self) -> AccessType {
+		self.next_accesses()[0]
+	}
+
+	fn next_accesses(&self) -> &'static [AccessType] {
+		if self
+			.range
+			.aspect_mask
+			.intersects(vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL)
+		{
+			&[AccessType::DepthStencilResolveWrite]
+		} else {
+			&[AccessType::ColorAttachmentWrite]
+		}
+	}
+}
+
+/// Transitions every attachment (and resolve attachment) for rendering with a
+/// single `cmd::pipeline_barrier`, returning the rendering attachment infos in
+/// the same order as `attachments` - see `RenderingAttachment::attachment_info`.
+///
+/// Nothing is recorded if none of the barriers are required, e.g. when every
+/// attachment is already in the right layout without a hazard.
+/// `command_buffer` is passed unmodified to `vkCmdPipelineBarrier`.
+pub fn begin_rendering_barriers(
+	device: &ash::Device,
+	command_buffer: vk::CommandBuffer,
+	attachments: &[RenderingAttachment],
+) -> Vec<vk::RenderingAttachmentInfo<'static>> {
+	let mut image_barriers = Vec::with_capacity(attachments.len());
+	for attachment in attachments {
+		image_barriers.push(attachment.begin_barrier());
+		if let Some(resolve) = &attachment.resolve {
+			image_barriers.push(resolve.begin_barrier());
+		}
+	}
+
+	cmd::pipeline_barrier(device, command_buffer, None, &[], &image_barriers);
+
+	attachments
+		.iter()
+		.map(RenderingAttachment::attachment_info)
+		.collect()
+}
+
+/// Transitions every attachment (and resolve attachment) after rendering with a
+/// single `cmd::pipeline_barrier`, before the accesses given by the element of
+/// `ends` at the same index.
+///
+/// Nothing is recorded if none of the barriers are required.
+/// `command_buffer` is passed unmodified to `vkCmdPipelineBarrier`.
+///
+/// # Panics
+///
+/// Panics if `attachments` and `ends` have different lengths.
+pub fn end_rendering_barriers<'a>(
+	device: &ash::Device,
+	command_buffer: vk::CommandBuffer,
+	attachments: &[RenderingAttachment<'a>],
+	ends: &[RenderingAttachmentEnd<'a>],
+) {
+	assert_eq!(
+		attachments.len(),
+		ends.len(),
+		"Every attachment needs the accesses after rendering"
+	);
+
+	let mut image_barriers = Vec::with_capacity(attachments.len());
+	for (attachment, end) in attachments.iter().zip(ends) {
+		if !end.next_accesses.is_empty() {
+			image_barriers.push(attachment.end_barrier(end.next_accesses, end.next_layout));
+		}
+		if let Some(resolve) = &attachment.resolve
+			&& !end.resolve_next_accesses.is_empty()
+		{
+			image_barriers
+				.push(resolve.end_barrier(end.resolve_next_accesses, end.resolve_next_layout));
+		}
+	}
+
+	cmd::pipeline_barrier(device, command_buffer, None, &[], &image_barriers);
+}
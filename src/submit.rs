@@ -0,0 +1,201 @@
+//! Queue submissions, with semaphore stage masks derived from access types.
+
+use super::*;
+use ash;
+
+/// The first accesses of an acquired swapchain image if none are given.
+const DEFAULT_ACQUIRE_ACCESSES: &[AccessType] = &[AccessType::ColorAttachmentWrite];
+
+fn acquire_accesses(first_accesses: &[AccessType]) -> &[AccessType] {
+	if first_accesses.is_empty() {
+		DEFAULT_ACQUIRE_ACCESSES
+	} else {
+		first_accesses
+	}
+}
+
+/// Builds the barrier transitioning a swapchain image acquired with
+/// `SubmitBuilder::wait_acquire` out of its presentation layout, before
+/// `first_accesses` (`AccessType::ColorAttachmentWrite` if empty).
+///
+/// The barrier waits on the same stages as the acquire semaphore, so that the
+/// layout transition happens after the semaphore wait. The contents of the image
+/// are discarded, as the previous frame's contents aren't needed.
+pub fn acquire_image_barrier(
+	image: vk::Image,
+	range: vk::ImageSubresourceRange,
+	first_accesses: &[AccessType],
+) -> ImageBarrier<'_> {
+	let accesses = acquire_accesses(first_accesses);
+
+	ImageBarrier {
+		previous_accesses: accesses,
+		next_accesses: accesses,
+		discard_contents: true,
+		image,
+		range,
+		..Default::default()
+	}
+}
+
+/// Builds a queue submission from command buffers and semaphores annotated
+/// with the accesses they order - see `semaphore_wait_stages` and
+/// `semaphore_signal_stages`.
+///
+/// The same submission can be retrieved as a synchronization2
+/// `vk::SubmitInfo2`, or as a legacy `vk::SubmitInfo` (chained with a
+/// `vk::TimelineSemaphoreSubmitInfo` if timeline semaphores are used).
+#[derive(Debug, Default, Clone)]
+pub struct SubmitBuilder {
+	command_buffers: Vec<vk::CommandBuffer>,
+	command_buffer_infos: Vec<vk::CommandBufferSubmitInfo<'static>>,
+	wait_semaphores: Vec<vk::Semaphore>,
+	wait_values: Vec<u64>,
+	wait_stages: Vec<vk::PipelineStageFlags>,
+	wait_infos: Vec<vk::SemaphoreSubmitInfo<'static>>,
+	signal_semaphores: Vec<vk::Semaphore>,
+	signal_values: Vec<u64>,
+	signal_infos: Vec<vk::SemaphoreSubmitInfo<'static>>,
+	timeline: bool,
+}
+
+impl SubmitBuilder {
+	pub fn new() -> Self {
+		SubmitBuilder::default()
+	}
+
+	/// Adds a command buffer, executed after the ones added before it.
+	pub fn command_buffer(mut self, command_buffer: vk::CommandBuffer) -> Self {
+		self.command_buffers.push(command_buffer);
+		self.command_buffer_infos
+			.push(vk::CommandBufferSubmitInfo::default().command_buffer(command_buffer));
+		self
+	}
+
+	/// Waits on a binary semaphore before `next_accesses`.
+	pub fn wait(self, semaphore: vk::Semaphore, next_accesses: &[AccessType]) -> Self {
+		self.push_wait(semaphore, 0, next_accesses)
+	}
+
+	/// Waits for a timeline semaphore to reach `value` before `next_accesses`.
+	pub fn wait_timeline(
+		mut self,
+		semaphore: vk::Semaphore,
+		value: u64,
+		next_accesses: &[AccessType],
+	) -> Self {
+		self.timeline = true;
+		self.push_wait(semaphore, value, next_accesses)
+	}
+
+	/// Waits on the semaphore signaled by `vkAcquireNextImageKHR` before
+	/// `first_accesses` of the acquired swapchain image, e.g.
+	/// `AccessType::TransferWrite` when copying to it. If `first_accesses` is
+	/// empty, the image is assumed to be rendered to, waiting at the
+	/// `AccessType::ColorAttachmentWrite` stages.
+	///
+	/// Only those stages wait, so earlier stages can overlap with the
+	/// presentation engine still reading the image. The barrier transitioning the
+	/// image out of its presentation layout has to wait on the same stages - see
+	/// `acquire_image_barrier`.
+	pub fn wait_acquire(self, semaphore: vk::Semaphore, first_accesses: &[AccessType]) -> Self {
+		self.push_wait(semaphore, 0, acquire_accesses(first_accesses))
+	}
+
+	/// Signals a binary semaphore after `previous_accesses`.
+	pub fn signal(self, semaphore: vk::Semaphore, previous_accesses: &[AccessType]) -> Self {
+		self.push_signal(semaphore, 0, previous_accesses)
+	}
+
+	/// Signals `value` on a timeline semaphore after `previous_accesses`.
+	pub fn signal_timeline(
+		mut self,
+		semaphore: vk::Semaphore,
+		value: u64,
+		previous_accesses: &[AccessType],
+	) -> Self {
+		self.timeline = true;
+		self.push_signal(semaphore, value, previous_accesses)
+	}
+
+	/// Returns true if any timeline semaphore is waited on or signaled, in which
+	/// case the legacy `submit_info` has to be chained with
+	/// `timeline_semaphore_submit_info`.
+	pub fn uses_timeline_semaphores(&self) -> bool {
+		self.timeline
+	}
+
+	/// Returns the submission for `vkQueueSubmit2`.
+	pub fn submit_info2(&self) -> vk::SubmitInfo2<'_> {
+		vk::SubmitInfo2::default()
+			.wait_semaphore_infos(&self.wait_infos)
+			.command_buffer_infos(&self.command_buffer_infos)
+			.signal_semaphore_infos(&self.signal_infos)
+	}
+
+	/// Returns the submission for `vkQueueSubmit`.
+	///
+	/// Signal stages can't be expressed, so semaphores are signaled once every
+	/// command buffer completed.
+	pub fn submit_info(&self) -> vk::SubmitInfo<'_> {
+		vk::SubmitInfo::default()
+			.wait_semaphores(&self.wait_semaphores)
+			.wait_dst_stage_mask(&self.wait_stages)
+			.command_buffers(&self.command_buffers)
+			.signal_semaphores(&self.signal_semaphores)
+	}
+
+	/// Returns the timeline semaphore values to chain with `submit_info`.
+	/// Values of binary semaphores are ignored.
+	pub fn timeline_semaphore_submit_info(&self) -> vk::TimelineSemaphoreSubmitInfo<'_> {
+		vk::TimelineSemaphoreSubmitInfo::default()
+			.wait_semaphore_values(&self.wait_values)
+			.signal_semaphore_values(&self.signal_values)
+	}
+
+	/// Submits to `queue` with `vkQueueSubmit`, chaining the timeline semaphore
+	/// values if required.
+	pub fn submit(
+		&self,
+		device: &ash::Device,
+		queue: vk::Queue,
+		fence: vk::Fence,
+	) -> ash::prelude::VkResult<()> {
+		let mut timeline_info = self.timeline_semaphore_submit_info();
+		let mut submit_info = self.submit_info();
+		if self.timeline {
+			submit_info = submit_info.push_next(&mut timeline_info);
+		}
+
+		unsafe { device.queue_submit(queue, &[submit_info], fence) }
+	}
+
+	fn push_wait(
+		mut self,
+		semaphore: vk::Semaphore,
+		value: u64,
+		next_accesses: &[AccessType],
+	) -> Self {
+		let (stages, _) = semaphore_wait_stages(next_accesses);
+
+		self.wait_semaphores.push(semaphore);
+		self.wait_values.push(value);
+		self.wait_stages.push(stages);
+		self.wait_infos
+			.push(semaphore_wait_info(semaphore, value, next_accesses));
+		self
+	}
+
+	fn push_signal(
+		mut self,
+		semaphore: vk::Semaphore,
+		value: u64,
+		previous_accesses: &[AccessType],
+	) -> Self {
+		self.signal_semaphores.push(semaphore);
+		self.signal_values.push(value);
+		self.signal_infos
+			.push(semaphore_signal_info(semaphore, value, previous_accesses));
+		self
+	}
+}
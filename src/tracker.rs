@@ -0,0 +1,593 @@
+//! Automatic barrier generation from per-resource access state.
+//!
+//! A `ResourceTracker` remembers the last accesses of every buffer and image it
+//! has seen, so that call sites only need to declare the accesses they are about
+//! to perform. Each transition queues the minimal barrier required, which can
+//! then be inspected with `barriers` or recorded with `flush`.
+//!
+//! Transitions are batched until they are flushed, so each resource should be
+//! transitioned at most once between flushes.
+//!
+//! `ImageStateMap` tracks the individual subresources of an image, for when mip
+//! levels or array layers are accessed independently. Similarly, `BufferStateMap`
+//! tracks byte ranges of a sub-allocated buffer.
+
+use super::*;
+use std::collections::HashMap;
+
+mod buffer;
+mod image;
+
+pub use self::buffer::{BufferRangeTransition, BufferStateMap, BufferTransition};
+pub use self::image::{ImageStateMap, ImageTransition};
+
+/// A resource whose accesses are tracked by a `ResourceTracker`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Resource {
+	Buffer(vk::Buffer),
+	Image(vk::Image),
+}
+
+impl From<vk::Buffer> for Resource {
+	fn from(buffer: vk::Buffer) -> Self {
+		Resource::Buffer(buffer)
+	}
+}
+
+impl From<vk::Image> for Resource {
+	fn from(image: vk::Image) -> Self {
+		Resource::Image(image)
+	}
+}
+
+/// Accesses performed on a resource since its last write.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct AccessState {
+	/// The accesses of the last write, or empty if the contents are unchanged
+	/// since they were last made visible
+	writes: Vec<AccessType>,
+
+	/// Read accesses performed since the last write, all of which have
+	/// visibility of that write
+	reads: Vec<AccessType>,
+}
+
+impl AccessState {
+	fn new(accesses: &[AccessType]) -> Self {
+		let mut state = AccessState::default();
+		state.replace(accesses);
+		state
+	}
+
+	/// The accesses a subsequent barrier needs to wait on.
+	fn last_accesses(&self) -> &[AccessType] {
+		if self.reads.is_empty() {
+			&self.writes
+		} else {
+			&self.reads
+		}
+	}
+
+	fn replace(&mut self, accesses: &[AccessType]) {
+		self.writes.clear();
+		self.reads.clear();
+		for access in accesses.iter().copied() {
+			if access == AccessType::Nothing {
+				continue;
+			}
+
+			if is_write_access(access) {
+				self.writes.push(access);
+			} else {
+				self.reads.push(access);
+			}
+		}
+	}
+
+	/// Records `next_accesses`, returning the previous and next accesses of the
+	/// barrier required before them, or `None` if no barrier is needed at all.
+	fn transition(
+		&mut self,
+		next_accesses: &[AccessType],
+		layout_changes: bool,
+	) -> Option<(Vec<AccessType>, Vec<AccessType>)> {
+		let next_writes = next_accesses.iter().any(|access| is_write_access(*access));
+
+		if !next_writes && !layout_changes {
+			let new_reads: Vec<AccessType> = next_accesses
+				.iter()
+				.copied()
+				.filter(|access| *access != AccessType::Nothing && !self.reads.contains(access))
+				.collect();
+
+			// Read after read - only a previous write needs to be made visible,
+			// and only to stages and accesses it hasn't already been made visible to.
+			let required = !self.writes.is_empty() && !is_visible(&self.reads, &new_reads);
+			self.reads.extend_from_slice(&new_reads);
+
+			return if required {
+				Some((self.writes.clone(), new_reads))
+			} else {
+				None
+			};
+		}
+
+		let previous_accesses = self.last_accesses().to_vec();
+		self.replace(next_accesses);
+
+		// Nothing to wait on, and no layout transition to perform
+		if previous_accesses.is_empty() && !layout_changes {
+			return None;
+		}
+
+		Some((previous_accesses, next_accesses.to_vec()))
+	}
+}
+
+/// Returns true if the stages and accesses of `accesses` are covered by `visible`.
+fn is_visible(visible: &[AccessType], accesses: &[AccessType]) -> bool {
+	let mut visible_stages = vk::PipelineStageFlags::empty();
+	let mut visible_access = vk::AccessFlags::empty();
+	for access in visible {
+		let info = get_access_info(*access);
+		visible_stages |= info.stage_mask;
+		visible_access |= info.access_mask;
+	}
+
+	accesses.iter().all(|access| {
+		let info = get_access_info(*access);
+		visible_stages.contains(info.stage_mask) && visible_access.contains(info.access_mask)
+	})
+}
+
+#[derive(Debug, Clone)]
+struct ImageState {
+	accesses: AccessState,
+	layout: ImageLayout,
+	native_layout: vk::ImageLayout,
+	range: vk::ImageSubresourceRange,
+}
+
+#[derive(Debug, Clone)]
+struct PendingGlobal {
+	previous_accesses: Vec<AccessType>,
+	next_accesses: Vec<AccessType>,
+}
+
+impl PendingGlobal {
+	/// Merges the accesses into this barrier, returning false if the merged
+	/// barrier would be invalid.
+	fn fold(&mut self, previous_accesses: &[AccessType], next_accesses: &[AccessType]) -> bool {
+		let merged_previous = union(&self.previous_accesses, previous_accesses);
+		let merged_next = union(&self.next_accesses, next_accesses);
+
+		let barrier = GlobalBarrier {
+			previous_accesses: &merged_previous,
+			next_accesses: &merged_next,
+		};
+		if validate_global_barrier(&barrier).is_err() {
+			return false;
+		}
+
+		self.previous_accesses = merged_previous;
+		self.next_accesses = merged_next;
+		true
+	}
+}
+
+#[derive(Debug, Clone)]
+struct PendingImage {
+	image: vk::Image,
+	previous_accesses: Vec<AccessType>,
+	next_accesses: Vec<AccessType>,
+	previous_layout: ImageLayout,
+	next_layout: ImageLayout,
+	discard_contents: bool,
+	range: vk::ImageSubresourceRange,
+}
+
+/// Barriers queued by a `ResourceTracker`, borrowed from the tracker.
+///
+/// There is usually at most one global barrier. Further global barriers are
+/// only queued when merging them would produce an invalid barrier, e.g. a write
+/// to one buffer alongside a read of another.
+#[derive(Debug, Default, Clone)]
+pub struct Barriers<'a> {
+	pub global_barriers: Vec<GlobalBarrier<'a>>,
+	pub image_barriers: Vec<ImageBarrier<'a>>,
+}
+
+impl Barriers<'_> {
+	/// Returns true if no barriers are queued.
+	pub fn is_empty(&self) -> bool {
+		self.global_barriers.is_empty() && self.image_barriers.is_empty()
+	}
+
+	/// Records the barriers with `cmd::pipeline_barrier`, if there are any.
+	/// Every global barrier after the first is recorded with a separate call.
+	pub fn record(&self, device: &ash::Device, command_buffer: vk::CommandBuffer) {
+		if self.is_empty() {
+			return;
+		}
+
+		let mut global_barriers = self.global_barriers.iter().cloned();
+		cmd::pipeline_barrier(
+			device,
+			command_buffer,
+			global_barriers.next(),
+			&[],
+			&self.image_barriers,
+		);
+		for global_barrier in global_barriers {
+			cmd::pipeline_barrier(device, command_buffer, Some(global_barrier), &[], &[]);
+		}
+	}
+
+	/// Records the barriers with `cmd::pipeline_barrier2`, if there are any.
+	/// Every global barrier after the first is recorded with a separate call.
+	pub fn record2<D: cmd::Synchronization2 + ?Sized>(
+		&self,
+		device: &D,
+		command_buffer: vk::CommandBuffer,
+	) {
+		if self.is_empty() {
+			return;
+		}
+
+		let mut global_barriers = self.global_barriers.iter().cloned();
+		cmd::pipeline_barrier2(
+			device,
+			command_buffer,
+			global_barriers.next(),
+			&[],
+			&self.image_barriers,
+		);
+		for global_barrier in global_barriers {
+			cmd::pipeline_barrier2(device, command_buffer, Some(global_barrier), &[], &[]);
+		}
+	}
+}
+
+/// Barriers queued by a `ResourceTracker`, owned rather than borrowed from the
+/// tracker - see `ResourceTracker::take_barriers`.
+#[derive(Debug, Default, Clone)]
+pub struct PendingBarriers {
+	global_barriers: Vec<PendingGlobal>,
+	image_barriers: Vec<PendingImage>,
+}
+
+impl PendingBarriers {
+	/// Returns true if no barriers are queued.
+	pub fn is_empty(&self) -> bool {
+		self.global_barriers.is_empty() && self.image_barriers.is_empty()
+	}
+
+	/// Merges the accesses into the first queued global barrier they can be
+	/// merged into, or queues another global barrier if there is none.
+	fn push_global(&mut self, previous_accesses: &[AccessType], next_accesses: &[AccessType]) {
+		for global in &mut self.global_barriers {
+			if global.fold(previous_accesses, next_accesses) {
+				return;
+			}
+		}
+
+		self.global_barriers.push(PendingGlobal {
+			previous_accesses: previous_accesses.to_vec(),
+			next_accesses: next_accesses.to_vec(),
+		});
+	}
+
+	/// Returns the queued barriers.
+	pub fn barriers(&self) -> Barriers<'_> {
+		Barriers {
+			global_barriers: self
+				.global_barriers
+				.iter()
+				.map(|pending| GlobalBarrier {
+					previous_accesses: &pending.previous_accesses,
+					next_accesses: &pending.next_accesses,
+				})
+				.collect(),
+			image_barriers: self
+				.image_barriers
+				.iter()
+				.map(|pending| ImageBarrier {
+					previous_accesses: &pending.previous_accesses,
+					next_accesses: &pending.next_accesses,
+					previous_layout: pending.previous_layout,
+					next_layout: pending.next_layout,
+					discard_contents: pending.discard_contents,
+					queue_transfer: QueueTransfer::None,
+					image: pending.image,
+					range: pending.range,
+				})
+				.collect(),
+		}
+	}
+
+	fn clear(&mut self) {
+		self.global_barriers.clear();
+		self.image_barriers.clear();
+	}
+}
+
+/// Tracks the accesses of buffers and images, deriving the barriers needed
+/// between them.
+///
+/// Buffers and images transitioned without a layout change are folded into a
+/// single global barrier where possible, or into further global barriers when
+/// folding would produce an invalid barrier (e.g. a write to one buffer
+/// alongside a read of another). Image barriers are only queued for layout
+/// transitions.
+#[derive(Debug, Default, Clone)]
+pub struct ResourceTracker {
+	buffers: HashMap<vk::Buffer, AccessState>,
+	images: HashMap<vk::Image, ImageState>,
+	pending: PendingBarriers,
+}
+
+impl ResourceTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Starts tracking a buffer, whose contents were last accessed by `accesses`.
+	///
+	/// Registering buffers is optional - unknown buffers are assumed to have no
+	/// previous accesses.
+	pub fn register_buffer(&mut self, buffer: vk::Buffer, accesses: &[AccessType]) {
+		self.buffers.insert(buffer, AccessState::new(accesses));
+	}
+
+	/// Starts tracking an image, whose contents were last accessed by `accesses`
+	/// in `layout`. Transitions affect the whole of `range`, and use `layout`
+	/// unless `transition_image` is used.
+	///
+	/// # Panics
+	///
+	/// Panics if the accesses don't resolve to a single image layout.
+	pub fn register_image(
+		&mut self,
+		image: vk::Image,
+		range: vk::ImageSubresourceRange,
+		layout: ImageLayout,
+		accesses: &[AccessType],
+	) {
+		let native_layout = resolve_image_layout(layout, accesses)
+			.unwrap_or_else(|error| panic!("Invalid image barrier: {}", error));
+
+		self.images.insert(
+			image,
+			ImageState {
+				accesses: AccessState::new(accesses),
+				layout,
+				native_layout,
+				range,
+			},
+		);
+	}
+
+	/// Stops tracking a resource, e.g. once it has been destroyed.
+	pub fn remove(&mut self, resource: impl Into<Resource>) {
+		match resource.into() {
+			Resource::Buffer(buffer) => {
+				self.buffers.remove(&buffer);
+			}
+			Resource::Image(image) => {
+				self.images.remove(&image);
+			}
+		}
+	}
+
+	/// Returns the accesses a subsequent barrier on a resource would wait on,
+	/// or `None` if the resource isn't tracked.
+	pub fn last_accesses(&self, resource: impl Into<Resource>) -> Option<&[AccessType]> {
+		match resource.into() {
+			Resource::Buffer(buffer) => {
+				self.buffers.get(&buffer).map(|state| state.last_accesses())
+			}
+			Resource::Image(image) => self
+				.images
+				.get(&image)
+				.map(|state| state.accesses.last_accesses()),
+		}
+	}
+
+	/// Declares that `resource` is about to be accessed by `next_accesses`,
+	/// queueing any barrier required beforehand.
+	///
+	/// Images keep the `ImageLayout` they were registered or last transitioned with.
+	///
+	/// # Panics
+	///
+	/// Panics if `resource` is an image that hasn't been registered, or if the
+	/// accesses are invalid - see `try_get_image_memory_barrier`.
+	pub fn transition(&mut self, resource: impl Into<Resource>, next_accesses: &[AccessType]) {
+		match resource.into() {
+			Resource::Buffer(buffer) => self.transition_buffer(buffer, next_accesses),
+			Resource::Image(image) => {
+				let layout = match self.images.get(&image) {
+					Some(state) => state.layout,
+					None => panic!("Image {:?} is not registered with the tracker", image),
+				};
+				self.transition_image(image, next_accesses, layout);
+			}
+		}
+	}
+
+	/// Declares that `image` is about to be accessed by `next_accesses` in
+	/// `next_layout`, queueing any barrier required beforehand.
+	///
+	/// # Panics
+	///
+	/// Panics if `image` hasn't been registered, or if the accesses are
+	/// invalid - see `try_get_image_memory_barrier`.
+	pub fn transition_image(
+		&mut self,
+		image: vk::Image,
+		next_accesses: &[AccessType],
+		next_layout: ImageLayout,
+	) {
+		let state = match self.images.get_mut(&image) {
+			Some(state) => state,
+			None => panic!("Image {:?} is not registered with the tracker", image),
+		};
+
+		let native_layout = resolve_image_layout(next_layout, next_accesses)
+			.unwrap_or_else(|error| panic!("Invalid image barrier: {}", error));
+		let layout_changes = native_layout != state.native_layout;
+		let previous_layout = state.layout;
+
+		let (previous_accesses, next_accesses) =
+			match state.accesses.transition(next_accesses, layout_changes) {
+				Some(accesses) => accesses,
+				None => return,
+			};
+
+		state.layout = next_layout;
+		state.native_layout = native_layout;
+		let range = state.range;
+
+		if layout_changes {
+			self.pending.image_barriers.push(PendingImage {
+				image,
+				previous_accesses,
+				next_accesses,
+				previous_layout,
+				next_layout,
+				discard_contents: false,
+				range,
+			});
+		} else {
+			self.pending.push_global(&previous_accesses, &next_accesses);
+		}
+	}
+
+	/// Declares that `image` is about to be accessed by `next_accesses` in
+	/// `next_layout`, reusing the memory of `outgoing` - e.g. when aliasing
+	/// transient resources.
+	///
+	/// This queues a barrier discarding the contents of `image`, which waits on
+	/// the last accesses of `outgoing` (see `aliasing_image_barrier`).
+	///
+	/// # Panics
+	///
+	/// Panics if `image` hasn't been registered, or if the accesses are
+	/// invalid - see `try_get_image_memory_barrier`.
+	pub fn transition_aliased_image(
+		&mut self,
+		outgoing: impl Into<Resource>,
+		image: vk::Image,
+		next_accesses: &[AccessType],
+		next_layout: ImageLayout,
+	) {
+		let previous_accesses = self
+			.last_accesses(outgoing)
+			.map(<[AccessType]>::to_vec)
+			.unwrap_or_default();
+
+		let state = match self.images.get_mut(&image) {
+			Some(state) => state,
+			None => panic!("Image {:?} is not registered with the tracker", image),
+		};
+
+		let native_layout = resolve_image_layout(next_layout, next_accesses)
+			.unwrap_or_else(|error| panic!("Invalid image barrier: {}", error));
+
+		state.accesses = AccessState::new(next_accesses);
+		state.layout = next_layout;
+		state.native_layout = native_layout;
+
+		self.pending.image_barriers.push(PendingImage {
+			image,
+			previous_accesses,
+			next_accesses: next_accesses.to_vec(),
+			previous_layout: ImageLayout::default(),
+			next_layout,
+			discard_contents: true,
+			range: state.range,
+		});
+	}
+
+	/// Declares that `buffer` is about to be accessed by `next_accesses`,
+	/// reusing the memory of `outgoing` - see `transition_aliased_image`.
+	///
+	/// This queues a global barrier waiting on the last accesses of `outgoing`
+	/// (see `aliasing_buffer_barrier`), which may be a buffer or an image.
+	pub fn transition_aliased_buffer(
+		&mut self,
+		outgoing: impl Into<Resource>,
+		buffer: vk::Buffer,
+		next_accesses: &[AccessType],
+	) {
+		let previous_accesses = self
+			.last_accesses(outgoing)
+			.map(<[AccessType]>::to_vec)
+			.unwrap_or_default();
+
+		self.buffers.insert(buffer, AccessState::new(next_accesses));
+
+		// Nothing to wait on
+		if previous_accesses.is_empty() {
+			return;
+		}
+
+		self.pending.push_global(&previous_accesses, next_accesses);
+	}
+
+	fn transition_buffer(&mut self, buffer: vk::Buffer, next_accesses: &[AccessType]) {
+		let (previous_accesses, next_accesses) = match self
+			.buffers
+			.entry(buffer)
+			.or_default()
+			.transition(next_accesses, false)
+		{
+			Some(accesses) => accesses,
+			None => return,
+		};
+
+		self.pending.push_global(&previous_accesses, &next_accesses);
+	}
+
+	/// Returns the barriers queued since the last flush.
+	pub fn barriers(&self) -> Barriers<'_> {
+		self.pending.barriers()
+	}
+
+	/// Removes the barriers queued since the last flush, returning them, e.g. to
+	/// record them later.
+	pub fn take_barriers(&mut self) -> PendingBarriers {
+		std::mem::take(&mut self.pending)
+	}
+
+	/// Discards the queued barriers, e.g. after recording them manually.
+	pub fn clear_barriers(&mut self) {
+		self.pending.clear();
+	}
+
+	/// Records the queued barriers with `cmd::pipeline_barrier`, if there are any.
+	pub fn flush(&mut self, device: &ash::Device, command_buffer: vk::CommandBuffer) {
+		self.barriers().record(device, command_buffer);
+		self.clear_barriers();
+	}
+
+	/// Records the queued barriers with `cmd::pipeline_barrier2`, if there are any.
+	pub fn flush2<D: cmd::Synchronization2 + ?Sized>(
+		&mut self,
+		device: &D,
+		command_buffer: vk::CommandBuffer,
+	) {
+		self.barriers().record2(device, command_buffer);
+		self.clear_barriers();
+	}
+}
+
+fn union(accesses: &[AccessType], other: &[AccessType]) -> Vec<AccessType> {
+	let mut union = accesses.to_vec();
+	for access in other {
+		if !union.contains(access) {
+			union.push(*access);
+		}
+	}
+	union
+}
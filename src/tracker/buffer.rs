@@ -0,0 +1,254 @@
+//! Buffer range state tracking.
+
+use super::{AccessState, union};
+use crate::*;
+use std::collections::BTreeMap;
+
+/// A transition of a byte range that shares the same previous state.
+#[derive(Debug, Clone)]
+pub struct BufferRangeTransition {
+	pub previous_accesses: Vec<AccessType>,
+	pub next_accesses: Vec<AccessType>,
+	pub offset: usize,
+	pub size: usize,
+}
+
+/// The transitions required before accessing a range of a buffer, produced by
+/// `BufferStateMap::transition`.
+///
+/// Without a queue family ownership transfer, prefer `global_barriers` as the
+/// `BufferBarrier` docs recommend - buffer barriers are only needed for
+/// ownership transfers.
+#[derive(Debug, Default, Clone)]
+pub struct BufferTransition {
+	ranges: Vec<BufferRangeTransition>,
+	global_previous_accesses: Vec<AccessType>,
+	global_next_accesses: Vec<AccessType>,
+}
+
+impl BufferTransition {
+	/// Returns true if no barrier is required.
+	pub fn is_empty(&self) -> bool {
+		self.ranges.is_empty()
+	}
+
+	/// Returns the ranges that require a barrier.
+	pub fn ranges(&self) -> &[BufferRangeTransition] {
+		&self.ranges
+	}
+
+	/// Returns a single global barrier covering every range, or `None` if no
+	/// barrier is required or the ranges can't be merged into a valid global
+	/// barrier (e.g. one range was last written, while another was last read).
+	pub fn global_barrier(&self) -> Option<GlobalBarrier<'_>> {
+		let barrier = GlobalBarrier {
+			previous_accesses: &self.global_previous_accesses,
+			next_accesses: &self.global_next_accesses,
+		};
+
+		if self.is_empty() || validate_global_barrier(&barrier).is_err() {
+			None
+		} else {
+			Some(barrier)
+		}
+	}
+
+	/// Returns a buffer barrier for every range of `buffer` that requires one,
+	/// e.g. to transfer the ranges to another queue family.
+	///
+	/// `queue_transfer` is passed unmodified into each barrier.
+	pub fn buffer_barriers(
+		&self,
+		buffer: vk::Buffer,
+		queue_transfer: QueueTransfer,
+	) -> Vec<BufferBarrier<'_>> {
+		self.ranges
+			.iter()
+			.map(|range| BufferBarrier {
+				previous_accesses: &range.previous_accesses,
+				next_accesses: &range.next_accesses,
+				queue_transfer,
+				buffer,
+				offset: range.offset,
+				size: range.size,
+			})
+			.collect()
+	}
+
+	/// Returns the global barriers to record without a queue family ownership
+	/// transfer - a single global barrier where possible, falling back to one
+	/// global barrier per range otherwise.
+	pub fn global_barriers(&self) -> Vec<GlobalBarrier<'_>> {
+		match self.global_barrier() {
+			Some(global_barrier) => vec![global_barrier],
+			None => self
+				.ranges
+				.iter()
+				.map(|range| GlobalBarrier {
+					previous_accesses: &range.previous_accesses,
+					next_accesses: &range.next_accesses,
+				})
+				.collect(),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+	end: usize,
+	accesses: AccessState,
+}
+
+/// Tracks the state of byte ranges of a buffer, e.g. when sub-allocating a large
+/// buffer for unrelated uses.
+///
+/// Ranges are split when partially overlapped by a transition, and merged again
+/// when adjacent ranges end up in the same state.
+#[derive(Debug, Clone)]
+pub struct BufferStateMap {
+	size: usize,
+	segments: BTreeMap<usize, Segment>,
+}
+
+impl BufferStateMap {
+	/// Creates a map for a buffer of `size` bytes, all of which were last
+	/// accessed by `accesses`.
+	pub fn new(size: usize, accesses: &[AccessType]) -> Self {
+		let mut segments = BTreeMap::new();
+		segments.insert(
+			0,
+			Segment {
+				end: size,
+				accesses: AccessState::new(accesses),
+			},
+		);
+
+		BufferStateMap { size, segments }
+	}
+
+	/// Returns the accesses a subsequent barrier on the byte at `offset` would
+	/// wait on.
+	///
+	/// # Panics
+	///
+	/// Panics if `offset` is outside of the buffer.
+	pub fn last_accesses(&self, offset: usize) -> &[AccessType] {
+		assert!(
+			offset < self.size,
+			"Offset {} is outside of the buffer",
+			offset
+		);
+		let (_, segment) = self.segments.range(..=offset).next_back().unwrap();
+		segment.accesses.last_accesses()
+	}
+
+	/// Declares that `size` bytes at `offset` are about to be accessed by
+	/// `next_accesses`, returning the transitions required beforehand.
+	///
+	/// `size` can be `vk::WHOLE_SIZE`, covering the rest of the buffer.
+	///
+	/// # Panics
+	///
+	/// Panics if the range is outside of the buffer, including when `offset + size`
+	/// overflows.
+	pub fn transition(
+		&mut self,
+		offset: usize,
+		size: usize,
+		next_accesses: &[AccessType],
+	) -> BufferTransition {
+		let end = if size == vk::WHOLE_SIZE as usize {
+			self.size
+		} else {
+			offset
+				.checked_add(size)
+				.unwrap_or_else(|| panic!("Range {}+{} is outside of the buffer", offset, size))
+		};
+		assert!(
+			offset <= end && end <= self.size,
+			"Range {}..{} is outside of the buffer",
+			offset,
+			end
+		);
+
+		let mut transition = BufferTransition::default();
+		if offset == end {
+			return transition;
+		}
+
+		self.split_at(offset);
+		self.split_at(end);
+
+		for (start, segment) in self.segments.range_mut(offset..end) {
+			let (previous_accesses, next_accesses) =
+				match segment.accesses.transition(next_accesses, false) {
+					Some(accesses) => accesses,
+					None => continue,
+				};
+
+			transition.global_previous_accesses =
+				union(&transition.global_previous_accesses, &previous_accesses);
+			transition.global_next_accesses =
+				union(&transition.global_next_accesses, &next_accesses);
+
+			// Coalesce with the previous range if it shares the same previous state
+			match transition.ranges.last_mut() {
+				Some(last)
+					if last.offset + last.size == *start
+						&& last.previous_accesses == previous_accesses
+						&& last.next_accesses == next_accesses =>
+				{
+					last.size += segment.end - start;
+				}
+				_ => transition.ranges.push(BufferRangeTransition {
+					previous_accesses,
+					next_accesses,
+					offset: *start,
+					size: segment.end - start,
+				}),
+			}
+		}
+
+		self.merge(offset, end);
+		transition
+	}
+
+	/// Splits the segment containing `at`, so that a segment starts at `at`.
+	fn split_at(&mut self, at: usize) {
+		if at >= self.size || self.segments.contains_key(&at) {
+			return;
+		}
+
+		let (_, segment) = self.segments.range_mut(..at).next_back().unwrap();
+		let tail = Segment {
+			end: segment.end,
+			accesses: segment.accesses.clone(),
+		};
+		segment.end = at;
+		self.segments.insert(at, tail);
+	}
+
+	/// Merges adjacent segments in the same state, between the segment
+	/// containing `start` and the segment starting at `end`.
+	fn merge(&mut self, start: usize, end: usize) {
+		let first = match self.segments.range(..start).next_back() {
+			Some((first, _)) => *first,
+			None => start,
+		};
+		let starts: Vec<usize> = self
+			.segments
+			.range(first..=end)
+			.map(|(start, _)| *start)
+			.collect();
+
+		let mut current = starts[0];
+		for next in starts.into_iter().skip(1) {
+			if self.segments[&current].accesses == self.segments[&next].accesses {
+				let removed = self.segments.remove(&next).unwrap();
+				self.segments.get_mut(&current).unwrap().end = removed.end;
+			} else {
+				current = next;
+			}
+		}
+	}
+}
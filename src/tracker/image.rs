@@ -0,0 +1,317 @@
+//! Per-subresource image state tracking.
+
+use super::AccessState;
+use crate::*;
+
+/// A transition of a subresource range that shares the same previous state,
+/// produced by `ImageStateMap::transition`.
+#[derive(Debug, Clone)]
+pub struct ImageTransition {
+	pub previous_accesses: Vec<AccessType>,
+	pub next_accesses: Vec<AccessType>,
+	pub previous_layout: ImageLayout,
+	pub next_layout: ImageLayout,
+	pub range: vk::ImageSubresourceRange,
+}
+
+impl ImageTransition {
+	/// Builds the image barrier for this transition on `image`.
+	pub fn to_barrier(&self, image: vk::Image) -> ImageBarrier<'_> {
+		ImageBarrier {
+			previous_accesses: &self.previous_accesses,
+			next_accesses: &self.next_accesses,
+			previous_layout: self.previous_layout,
+			next_layout: self.next_layout,
+			discard_contents: false,
+			queue_transfer: QueueTransfer::None,
+			image,
+			range: self.range,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+struct SubresourceState {
+	accesses: AccessState,
+	layout: ImageLayout,
+	native_layout: vk::ImageLayout,
+}
+
+/// The barrier required by a single subresource - previous accesses, next
+/// accesses and previous layout.
+type TransitionKey = (Vec<AccessType>, Vec<AccessType>, ImageLayout);
+
+/// A box of subresources sharing the same transition.
+#[derive(Debug, Copy, Clone)]
+struct Run {
+	aspect_mask: vk::ImageAspectFlags,
+	base_mip_level: u32,
+	level_count: u32,
+	base_array_layer: u32,
+	layer_count: u32,
+	key: usize,
+}
+
+/// Tracks the state of every subresource of an image individually, so that
+/// different mip levels, array layers and aspects can be in different layouts
+/// and accessed by different passes.
+///
+/// Transitions return the minimal set of barriers for the requested range,
+/// coalescing adjacent subresources that share a previous state - first across
+/// array layers, then mip levels, then aspects.
+#[derive(Debug, Clone)]
+pub struct ImageStateMap {
+	aspects: Vec<vk::ImageAspectFlags>,
+	mip_levels: u32,
+	array_layers: u32,
+	subresources: Vec<SubresourceState>,
+}
+
+impl ImageStateMap {
+	/// Creates a map for an image with the given aspects, mip levels and array
+	/// layers, all of which were last accessed by `accesses` in `layout`.
+	///
+	/// # Panics
+	///
+	/// Panics if the accesses don't resolve to a single image layout.
+	pub fn new(
+		aspect_mask: vk::ImageAspectFlags,
+		mip_levels: u32,
+		array_layers: u32,
+		layout: ImageLayout,
+		accesses: &[AccessType],
+	) -> Self {
+		let native_layout = resolve_image_layout(layout, accesses)
+			.unwrap_or_else(|error| panic!("Invalid image barrier: {}", error));
+
+		let aspects: Vec<vk::ImageAspectFlags> = (0..32)
+			.map(|bit| vk::ImageAspectFlags::from_raw(1 << bit))
+			.filter(|aspect| aspect_mask.contains(*aspect))
+			.collect();
+
+		let state = SubresourceState {
+			accesses: AccessState::new(accesses),
+			layout,
+			native_layout,
+		};
+		let count = aspects.len() * mip_levels as usize * array_layers as usize;
+
+		ImageStateMap {
+			aspects,
+			mip_levels,
+			array_layers,
+			subresources: vec![state; count],
+		}
+	}
+
+	/// Returns the range covering every subresource of the image.
+	pub fn full_range(&self) -> vk::ImageSubresourceRange {
+		vk::ImageSubresourceRange {
+			aspect_mask: self
+				.aspects
+				.iter()
+				.fold(vk::ImageAspectFlags::empty(), |mask, aspect| mask | *aspect),
+			base_mip_level: 0,
+			level_count: self.mip_levels,
+			base_array_layer: 0,
+			layer_count: self.array_layers,
+		}
+	}
+
+	/// Returns the accesses a subsequent barrier on a single subresource would
+	/// wait on, along with its current layout.
+	///
+	/// # Panics
+	///
+	/// Panics if the subresource is outside of the image.
+	pub fn subresource_state(
+		&self,
+		aspect: vk::ImageAspectFlags,
+		mip_level: u32,
+		array_layer: u32,
+	) -> (&[AccessType], ImageLayout) {
+		let aspect_index = self
+			.aspects
+			.iter()
+			.position(|candidate| *candidate == aspect)
+			.unwrap_or_else(|| panic!("Aspect {:?} is not part of the image", aspect));
+		let state = &self.subresources[self.index(aspect_index, mip_level, array_layer)];
+		(state.accesses.last_accesses(), state.layout)
+	}
+
+	/// Declares that `range` is about to be accessed by `next_accesses` in
+	/// `next_layout`, returning the transitions required beforehand.
+	///
+	/// `vk::REMAINING_MIP_LEVELS` and `vk::REMAINING_ARRAY_LAYERS` are resolved
+	/// against the image, so the returned ranges are always explicit.
+	///
+	/// # Panics
+	///
+	/// Panics if `range` is outside of the image, or if the accesses are
+	/// invalid - see `try_get_image_memory_barrier`.
+	pub fn transition(
+		&mut self,
+		range: vk::ImageSubresourceRange,
+		next_accesses: &[AccessType],
+		next_layout: ImageLayout,
+	) -> Vec<ImageTransition> {
+		let native_layout = resolve_image_layout(next_layout, next_accesses)
+			.unwrap_or_else(|error| panic!("Invalid image barrier: {}", error));
+
+		let level_count = if range.level_count == vk::REMAINING_MIP_LEVELS {
+			self.mip_levels.saturating_sub(range.base_mip_level)
+		} else {
+			range.level_count
+		};
+		let layer_count = if range.layer_count == vk::REMAINING_ARRAY_LAYERS {
+			self.array_layers.saturating_sub(range.base_array_layer)
+		} else {
+			range.layer_count
+		};
+		assert!(
+			range.base_mip_level + level_count <= self.mip_levels
+				&& range.base_array_layer + layer_count <= self.array_layers,
+			"Range {:?} is outside of the image",
+			range
+		);
+
+		let mut keys: Vec<TransitionKey> = Vec::new();
+		let mut runs: Vec<Run> = Vec::new();
+
+		for (aspect_index, aspect) in self.aspects.clone().into_iter().enumerate() {
+			if !range.aspect_mask.contains(aspect) {
+				continue;
+			}
+
+			// Runs of the previous mip level, which can be extended by this one
+			let mut previous_mip_runs = runs.len()..runs.len();
+
+			for mip_level in range.base_mip_level..range.base_mip_level + level_count {
+				let mip_runs_start = runs.len();
+				let mut layer_run: Option<Run> = None;
+
+				for array_layer in range.base_array_layer..range.base_array_layer + layer_count {
+					let index = self.index(aspect_index, mip_level, array_layer);
+					let key = self.transition_subresource(
+						index,
+						next_accesses,
+						next_layout,
+						native_layout,
+					);
+
+					let key = key.map(|key| match keys.iter().position(|other| *other == key) {
+						Some(position) => position,
+						None => {
+							keys.push(key);
+							keys.len() - 1
+						}
+					});
+
+					// Coalesce across array layers
+					match (layer_run.as_mut(), key) {
+						(Some(run), Some(key)) if run.key == key => run.layer_count += 1,
+						(_, key) => {
+							runs.extend(layer_run.take());
+							layer_run = key.map(|key| Run {
+								aspect_mask: aspect,
+								base_mip_level: mip_level,
+								level_count: 1,
+								base_array_layer: array_layer,
+								layer_count: 1,
+								key,
+							});
+						}
+					}
+				}
+				runs.extend(layer_run.take());
+
+				// Coalesce across mip levels, by extending an identical run of the
+				// previous mip level
+				let mut kept = mip_runs_start;
+				for current in mip_runs_start..runs.len() {
+					let run = runs[current];
+					let extended = runs[previous_mip_runs.clone()].iter_mut().find(|previous| {
+						previous.key == run.key
+							&& previous.base_array_layer == run.base_array_layer
+							&& previous.layer_count == run.layer_count
+							&& previous.base_mip_level + previous.level_count == mip_level
+					});
+
+					match extended {
+						Some(previous) => previous.level_count += 1,
+						None => {
+							runs[kept] = run;
+							kept += 1;
+						}
+					}
+				}
+				runs.truncate(kept);
+
+				// Any run of this aspect ending at this mip level can be extended by the next
+				previous_mip_runs = previous_mip_runs.start..runs.len();
+			}
+		}
+
+		// Coalesce across aspects
+		let mut merged: Vec<Run> = Vec::with_capacity(runs.len());
+		for run in runs {
+			let existing = merged.iter_mut().find(|other| {
+				other.key == run.key
+					&& other.base_mip_level == run.base_mip_level
+					&& other.level_count == run.level_count
+					&& other.base_array_layer == run.base_array_layer
+					&& other.layer_count == run.layer_count
+			});
+
+			match existing {
+				Some(other) => other.aspect_mask |= run.aspect_mask,
+				None => merged.push(run),
+			}
+		}
+
+		merged
+			.into_iter()
+			.map(|run| {
+				let (previous_accesses, next_accesses, previous_layout) = keys[run.key].clone();
+				ImageTransition {
+					previous_accesses,
+					next_accesses,
+					previous_layout,
+					next_layout,
+					range: vk::ImageSubresourceRange {
+						aspect_mask: run.aspect_mask,
+						base_mip_level: run.base_mip_level,
+						level_count: run.level_count,
+						base_array_layer: run.base_array_layer,
+						layer_count: run.layer_count,
+					},
+				}
+			})
+			.collect()
+	}
+
+	fn index(&self, aspect_index: usize, mip_level: u32, array_layer: u32) -> usize {
+		assert!(mip_level < self.mip_levels && array_layer < self.array_layers);
+		(aspect_index * self.mip_levels as usize + mip_level as usize) * self.array_layers as usize
+			+ array_layer as usize
+	}
+
+	fn transition_subresource(
+		&mut self,
+		index: usize,
+		next_accesses: &[AccessType],
+		next_layout: ImageLayout,
+		native_layout: vk::ImageLayout,
+	) -> Option<TransitionKey> {
+		let state = &mut self.subresources[index];
+		let layout_changes = native_layout != state.native_layout;
+		let previous_layout = state.layout;
+
+		let (previous_accesses, next_accesses) =
+			state.accesses.transition(next_accesses, layout_changes)?;
+
+		state.layout = next_layout;
+		state.native_layout = native_layout;
+		Some((previous_accesses, next_accesses, previous_layout))
+	}
+}
@@ -0,0 +1,317 @@
+//! Opt-in validation of barrier definitions, enabled with the `validation` feature.
+//!
+//! When enabled, the `cmd` wrappers check every barrier before recording it and
+//! report any problems through a user-installable callback. The checks only run
+//! in builds with debug assertions enabled, so release builds compile them out
+//! entirely.
+//!
+//! Beyond the hard errors reported by the fallible mapping functions, this also
+//! catches definitions that translate fine but are almost certainly mistakes.
+
+use super::*;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+type Callback = Arc<dyn Fn(&ValidationMessage) + Send + Sync>;
+
+static CALLBACK: RwLock<Option<Callback>> = RwLock::new(None);
+
+/// A problem detected in a barrier definition.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ValidationError {
+	/// The barrier is invalid and can't be translated by the mapping functions
+	Barrier(BarrierError),
+
+	/// `AccessType::Nothing` was specified alongside other accesses
+	NothingMixedWithOtherAccesses,
+
+	/// `discard_contents` was combined with a queue family ownership transfer, which
+	/// would discard the contents being transferred
+	DiscardWithQueueTransfer,
+
+	/// An image barrier neither transitions the layout nor transfers queue family
+	/// ownership - a global barrier should be used instead
+	ImageBarrierWithoutTransition,
+
+	/// `vk::DependencyFlags::BY_REGION` was used, but the accesses aren't limited to
+	/// framebuffer-space stages
+	ByRegionOutsideFramebufferSpace,
+}
+
+/// Identifies which of the barriers passed to a `cmd` wrapper a message refers to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BarrierLocation {
+	Execution,
+	Global,
+	Buffer(usize),
+	Image(usize),
+}
+
+/// A validation error, along with the barrier it was found in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ValidationMessage {
+	pub location: BarrierLocation,
+	pub error: ValidationError,
+}
+
+impl fmt::Display for ValidationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ValidationError::Barrier(error) => error.fmt(f),
+			ValidationError::NothingMixedWithOtherAccesses => {
+				write!(f, "AccessType::Nothing specified alongside other accesses")
+			}
+			ValidationError::DiscardWithQueueTransfer => write!(
+				f,
+				"discard_contents combined with a queue family ownership transfer"
+			),
+			ValidationError::ImageBarrierWithoutTransition => write!(
+				f,
+				"image barrier without a layout transition or queue family ownership transfer, prefer a global barrier"
+			),
+			ValidationError::ByRegionOutsideFramebufferSpace => write!(
+				f,
+				"BY_REGION dependency with accesses outside of framebuffer-space stages"
+			),
+		}
+	}
+}
+
+impl fmt::Display for ValidationMessage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.location {
+			BarrierLocation::Execution => write!(f, "execution barrier: {}", self.error),
+			BarrierLocation::Global => write!(f, "global barrier: {}", self.error),
+			BarrierLocation::Buffer(index) => {
+				write!(f, "buffer barrier {}: {}", index, self.error)
+			}
+			BarrierLocation::Image(index) => write!(f, "image barrier {}: {}", index, self.error),
+		}
+	}
+}
+
+/// Installs the callback that validation messages are reported through,
+/// replacing any previously installed callback.
+///
+/// Without a callback, messages are dropped. The callback may install or clear
+/// callbacks itself, and only applies to messages reported after that.
+pub fn set_callback<F>(callback: F)
+where
+	F: Fn(&ValidationMessage) + Send + Sync + 'static,
+{
+	*CALLBACK.write().unwrap_or_else(|error| error.into_inner()) = Some(Arc::new(callback));
+}
+
+/// Removes the installed callback, so that messages are dropped.
+pub fn clear_callback() {
+	*CALLBACK.write().unwrap_or_else(|error| error.into_inner()) = None;
+}
+
+/// Checks a global barrier, returning every problem found.
+pub fn validate_global_barrier(barrier: &GlobalBarrier) -> Vec<ValidationError> {
+	let mut errors = Vec::new();
+
+	if let Err(error) = super::validate_global_barrier(barrier) {
+		errors.push(ValidationError::Barrier(error));
+	}
+
+	check_nothing_accesses(
+		barrier.previous_accesses,
+		barrier.next_accesses,
+		&mut errors,
+	);
+
+	errors
+}
+
+/// Checks a buffer barrier, returning every problem found.
+pub fn validate_buffer_barrier(barrier: &BufferBarrier) -> Vec<ValidationError> {
+	let mut errors = Vec::new();
+
+	if let Err(error) = super::validate_buffer_barrier(barrier) {
+		errors.push(ValidationError::Barrier(error));
+	}
+
+	check_nothing_accesses(
+		barrier.previous_accesses,
+		barrier.next_accesses,
+		&mut errors,
+	);
+
+	errors
+}
+
+/// Checks an image barrier, returning every problem found.
+pub fn validate_image_barrier(barrier: &ImageBarrier) -> Vec<ValidationError> {
+	let mut errors = Vec::new();
+
+	let queue_transfer = barrier.queue_transfer.is_transfer();
+
+	match get_image_barrier_layouts(barrier) {
+		Ok((old_layout, new_layout)) => {
+			if old_layout == new_layout && !queue_transfer {
+				errors.push(ValidationError::ImageBarrierWithoutTransition);
+			}
+		}
+		Err(error) => errors.push(ValidationError::Barrier(error)),
+	}
+
+	check_nothing_accesses(
+		barrier.previous_accesses,
+		barrier.next_accesses,
+		&mut errors,
+	);
+
+	if barrier.discard_contents && queue_transfer {
+		errors.push(ValidationError::DiscardWithQueueTransfer);
+	}
+
+	errors
+}
+
+/// Checks that the accesses of a barrier are compatible with `dependency_flags`,
+/// returning every problem found.
+///
+/// `BY_REGION` dependencies are only meaningful between framebuffer-space
+/// stages - fragment shading, fragment tests and color attachment output.
+/// Accesses without a stage (e.g. `AccessType::Nothing`) are ignored.
+pub fn validate_dependency_flags(
+	dependency_flags: vk::DependencyFlags,
+	previous_accesses: &[AccessType],
+	next_accesses: &[AccessType],
+) -> Vec<ValidationError> {
+	let mut errors = Vec::new();
+
+	if dependency_flags.contains(vk::DependencyFlags::BY_REGION) {
+		let framebuffer_space = |accesses: &[AccessType]| {
+			accesses.iter().all(|access| {
+				FRAMEBUFFER_SPACE_STAGES.contains(get_access_info(*access).stage_mask)
+			})
+		};
+
+		if !framebuffer_space(previous_accesses) || !framebuffer_space(next_accesses) {
+			errors.push(ValidationError::ByRegionOutsideFramebufferSpace);
+		}
+	}
+
+	errors
+}
+
+const FRAMEBUFFER_SPACE_STAGES: vk::PipelineStageFlags = vk::PipelineStageFlags::from_raw(
+	vk::PipelineStageFlags::FRAGMENT_SHADER.as_raw()
+		| vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS.as_raw()
+		| vk::PipelineStageFlags::LATE_FRAGMENT_TESTS.as_raw()
+		| vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT.as_raw(),
+);
+
+/// Checks every barrier passed to a `cmd` wrapper, and reports the problems
+/// through the installed callback.
+pub(crate) fn check_barriers(
+	global_barrier: Option<&GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	if let Some(barrier) = global_barrier {
+		report(BarrierLocation::Global, validate_global_barrier(barrier));
+	}
+
+	for (index, barrier) in buffer_barriers.iter().enumerate() {
+		report(
+			BarrierLocation::Buffer(index),
+			validate_buffer_barrier(barrier),
+		);
+	}
+
+	for (index, barrier) in image_barriers.iter().enumerate() {
+		report(
+			BarrierLocation::Image(index),
+			validate_image_barrier(barrier),
+		);
+	}
+}
+
+/// Checks the dependency flags passed to a `cmd` wrapper against every barrier,
+/// and reports the problems through the installed callback.
+pub(crate) fn check_dependency_flags(
+	dependency_flags: vk::DependencyFlags,
+	execution_barrier: Option<&ExecutionBarrier>,
+	global_barrier: Option<&GlobalBarrier>,
+	buffer_barriers: &[BufferBarrier],
+	image_barriers: &[ImageBarrier],
+) {
+	if let Some(barrier) = execution_barrier {
+		report(
+			BarrierLocation::Execution,
+			validate_dependency_flags(
+				dependency_flags,
+				barrier.previous_accesses,
+				barrier.next_accesses,
+			),
+		);
+	}
+
+	if let Some(barrier) = global_barrier {
+		report(
+			BarrierLocation::Global,
+			validate_dependency_flags(
+				dependency_flags,
+				barrier.previous_accesses,
+				barrier.next_accesses,
+			),
+		);
+	}
+
+	for (index, barrier) in buffer_barriers.iter().enumerate() {
+		report(
+			BarrierLocation::Buffer(index),
+			validate_dependency_flags(
+				dependency_flags,
+				barrier.previous_accesses,
+				barrier.next_accesses,
+			),
+		);
+	}
+
+	for (index, barrier) in image_barriers.iter().enumerate() {
+		report(
+			BarrierLocation::Image(index),
+			validate_dependency_flags(
+				dependency_flags,
+				barrier.previous_accesses,
+				barrier.next_accesses,
+			),
+		);
+	}
+}
+
+fn check_nothing_accesses(
+	previous_accesses: &[AccessType],
+	next_accesses: &[AccessType],
+	errors: &mut Vec<ValidationError>,
+) {
+	for accesses in [previous_accesses, next_accesses] {
+		if accesses.len() > 1 && accesses.contains(&AccessType::Nothing) {
+			errors.push(ValidationError::NothingMixedWithOtherAccesses);
+		}
+	}
+}
+
+pub(crate) fn report(location: BarrierLocation, errors: Vec<ValidationError>) {
+	if errors.is_empty() {
+		return;
+	}
+
+	// Release the lock before calling back, so the callback can replace itself
+	let callback = match CALLBACK
+		.read()
+		.unwrap_or_else(|error| error.into_inner())
+		.as_ref()
+	{
+		Some(callback) => Arc::clone(callback),
+		None => return,
+	};
+
+	for error in errors {
+		callback(&ValidationMessage { location, error });
+	}
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use ash::vk;
+use ash::vk::Handle;
+use common::color_range;
+use vk_sync::tracker::ResourceTracker;
+
+#[test]
+fn image_aliasing_discards_contents() {
+	// Storage image written by compute, memory reused by a color attachment
+	let image_barrier = vk_sync::aliasing_image_barrier(
+		&[vk_sync::AccessType::ComputeShaderWrite],
+		&[vk_sync::AccessType::ColorAttachmentWrite],
+		vk_sync::ImageLayout::Optimal,
+		vk::Image::null(),
+		color_range(),
+	);
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_image_memory_barrier(&image_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::COMPUTE_SHADER);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::SHADER_WRITE);
+	assert_eq!(
+		barrier.dst_access_mask,
+		vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+	);
+	assert_eq!(barrier.old_layout, vk::ImageLayout::UNDEFINED);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+	assert_eq!(barrier.src_queue_family_index, vk::QUEUE_FAMILY_IGNORED);
+}
+
+#[test]
+fn buffer_aliasing() {
+	// Vertex buffer read by graphics, memory reused by a compute storage buffer
+	let global_barrier = vk_sync::aliasing_buffer_barrier(
+		&[vk_sync::AccessType::VertexBuffer],
+		&[vk_sync::AccessType::ComputeShaderWrite],
+	);
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_memory_barrier(&global_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::VERTEX_INPUT);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::COMPUTE_SHADER);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::empty());
+}
+
+#[test]
+fn image_to_buffer_aliasing() {
+	// Color attachment written by graphics, memory reused by a transfer destination
+	let global_barrier = vk_sync::aliasing_buffer_barrier(
+		&[vk_sync::AccessType::ColorAttachmentWrite],
+		&[vk_sync::AccessType::TransferWrite],
+	);
+
+	let (src_mask, dst_mask, barrier) = vk_sync::try_get_memory_barrier(&global_barrier).unwrap();
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::TRANSFER);
+	assert_eq!(
+		barrier.src_access_mask,
+		vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+	);
+}
+
+#[test]
+fn tracker_image_aliasing() {
+	// Two transient images sharing memory, the second one reusing it after the first
+	let first = vk::Image::from_raw(1);
+	let second = vk::Image::from_raw(2);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_image(
+		first,
+		color_range(),
+		vk_sync::ImageLayout::Optimal,
+		&[vk_sync::AccessType::ColorAttachmentWrite],
+	);
+	tracker.register_image(
+		second,
+		color_range(),
+		vk_sync::ImageLayout::Optimal,
+		&[vk_sync::AccessType::TransferRead],
+	);
+
+	tracker.transition_aliased_image(
+		first,
+		second,
+		&[vk_sync::AccessType::ComputeShaderWrite],
+		vk_sync::ImageLayout::General,
+	);
+
+	{
+		let barriers = tracker.barriers();
+		assert!(barriers.global_barriers.is_empty());
+		assert_eq!(barriers.image_barriers.len(), 1);
+
+		let image_barrier = &barriers.image_barriers[0];
+		assert!(image_barrier.discard_contents);
+		assert_eq!(
+			image_barrier.previous_accesses,
+			[vk_sync::AccessType::ColorAttachmentWrite]
+		);
+
+		let (src_mask, _, barrier) = vk_sync::get_image_memory_barrier(image_barrier);
+		assert_eq!(src_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+		assert_eq!(barrier.old_layout, vk::ImageLayout::UNDEFINED);
+		assert_eq!(barrier.new_layout, vk::ImageLayout::GENERAL);
+	}
+	tracker.clear_barriers();
+
+	// The incoming image is tracked from its first accesses onwards
+	assert_eq!(
+		tracker.last_accesses(second),
+		Some(&[vk_sync::AccessType::ComputeShaderWrite][..])
+	);
+}
+
+#[test]
+fn tracker_buffer_aliasing() {
+	let first = vk::Buffer::from_raw(1);
+	let second = vk::Buffer::from_raw(2);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_buffer(first, &[vk_sync::AccessType::TransferWrite]);
+
+	tracker.transition_aliased_buffer(first, second, &[vk_sync::AccessType::ComputeShaderWrite]);
+
+	let barriers = tracker.barriers();
+	assert_eq!(barriers.global_barriers.len(), 1);
+	let global_barrier = &barriers.global_barriers[0];
+	assert_eq!(
+		global_barrier.previous_accesses,
+		[vk_sync::AccessType::TransferWrite]
+	);
+	assert_eq!(
+		global_barrier.next_accesses,
+		[vk_sync::AccessType::ComputeShaderWrite]
+	);
+	assert!(barriers.image_barriers.is_empty());
+}
+
+#[test]
+fn tracker_image_to_buffer_aliasing() {
+	let image = vk::Image::from_raw(1);
+	let buffer = vk::Buffer::from_raw(2);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_image(
+		image,
+		color_range(),
+		vk_sync::ImageLayout::Optimal,
+		&[vk_sync::AccessType::ColorAttachmentWrite],
+	);
+
+	tracker.transition_aliased_buffer(image, buffer, &[vk_sync::AccessType::TransferWrite]);
+
+	let barriers = tracker.barriers();
+	assert!(barriers.image_barriers.is_empty());
+	assert_eq!(barriers.global_barriers.len(), 1);
+	let global_barrier = &barriers.global_barriers[0];
+	assert_eq!(
+		global_barrier.previous_accesses,
+		[vk_sync::AccessType::ColorAttachmentWrite]
+	);
+	assert_eq!(
+		global_barrier.next_accesses,
+		[vk_sync::AccessType::TransferWrite]
+	);
+	assert!(vk_sync::try_get_memory_barrier(global_barrier).is_ok());
+}
@@ -0,0 +1,113 @@
+use ash::vk;
+use ash::vk::Handle;
+use vk_sync::cmd::BarrierBatch;
+
+fn image_barrier(base_mip_level: u32) -> vk_sync::ImageBarrier<'static> {
+	vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::from_raw(1),
+		range: vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: 1,
+		},
+	}
+}
+
+#[test]
+fn merges_global_barriers() {
+	// Compute write to indirect buffer and upload of vertex data, before a draw
+	let mut batch = BarrierBatch::new();
+	batch.push_global(&vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::IndirectBuffer],
+	});
+	batch.push_global(&vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+	});
+
+	let (src_mask, dst_mask) = batch.stage_masks();
+	assert_eq!(
+		src_mask,
+		vk::PipelineStageFlags::COMPUTE_SHADER | vk::PipelineStageFlags::TRANSFER
+	);
+	assert_eq!(
+		dst_mask,
+		vk::PipelineStageFlags::DRAW_INDIRECT | vk::PipelineStageFlags::VERTEX_INPUT
+	);
+
+	let memory_barrier = batch.memory_barrier().unwrap();
+	assert_eq!(
+		memory_barrier.src_access_mask,
+		vk::AccessFlags::SHADER_WRITE | vk::AccessFlags::TRANSFER_WRITE
+	);
+	assert_eq!(
+		memory_barrier.dst_access_mask,
+		vk::AccessFlags::INDIRECT_COMMAND_READ | vk::AccessFlags::VERTEX_ATTRIBUTE_READ
+	);
+}
+
+#[test]
+fn dedupes_identical_image_barriers() {
+	let mut batch = BarrierBatch::new();
+	batch.push_image(&image_barrier(0));
+	batch.push_image(&image_barrier(0));
+	batch.push_image(&image_barrier(1));
+
+	assert_eq!(batch.image_barriers().len(), 2);
+	assert_eq!(
+		batch.image_barriers()[1].subresource_range.base_mip_level,
+		1
+	);
+	assert_eq!(
+		batch.image_barriers()[0].new_layout,
+		vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+	);
+}
+
+#[test]
+fn keeps_buffer_barriers() {
+	// Queue ownership transfer of a buffer, alongside an execution only dependency
+	let mut batch = BarrierBatch::new();
+	batch.push_buffer(&vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		queue_transfer: vk_sync::QueueTransfer::Transfer { src: 0, dst: 1 },
+		buffer: vk::Buffer::from_raw(1),
+		offset: 0,
+		size: 256,
+	});
+	batch.push_execution(&vk_sync::ExecutionBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+	});
+
+	assert!(batch.memory_barrier().is_none());
+	assert_eq!(batch.buffer_barriers().len(), 1);
+	assert_eq!(batch.buffer_barriers()[0].size, 256);
+	assert_eq!(
+		batch.stage_masks().0,
+		vk::PipelineStageFlags::TRANSFER | vk::PipelineStageFlags::COMPUTE_SHADER
+	);
+}
+
+#[test]
+fn clear() {
+	let mut batch = BarrierBatch::new();
+	assert!(batch.is_empty());
+
+	batch.push_image(&image_barrier(0));
+	assert!(!batch.is_empty());
+
+	batch.clear();
+	assert!(batch.is_empty());
+	assert!(batch.image_barriers().is_empty());
+}
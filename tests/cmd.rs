@@ -0,0 +1,579 @@
+//! Recording tests, using a mock synchronization2 device that captures the
+//! native barriers instead of calling into a Vulkan driver.
+
+mod common;
+
+use ash::vk;
+use ash::vk::Handle;
+use std::cell::RefCell;
+use std::ffi::c_void;
+
+unsafe fn slice<'a, T>(ptr: *const T, count: u32) -> &'a [T] {
+	if count == 0 {
+		&[]
+	} else {
+		unsafe { std::slice::from_raw_parts(ptr, count as usize) }
+	}
+}
+
+#[derive(Default)]
+struct Recorder {
+	memory_barriers: RefCell<Vec<(vk::PipelineStageFlags2, vk::PipelineStageFlags2)>>,
+	buffer_barriers: RefCell<Vec<(vk::PipelineStageFlags2, vk::PipelineStageFlags2)>>,
+	image_barriers: RefCell<Vec<(vk::PipelineStageFlags2, vk::PipelineStageFlags2)>>,
+	events: RefCell<Vec<(&'static str, vk::Event)>>,
+	reset_stages: RefCell<Vec<vk::PipelineStageFlags2>>,
+	dependency_flags: RefCell<Vec<vk::DependencyFlags>>,
+	calls: RefCell<usize>,
+}
+
+impl Recorder {
+	fn record(&self, dependency_info: &vk::DependencyInfo) {
+		let memory = unsafe {
+			slice(
+				dependency_info.p_memory_barriers,
+				dependency_info.memory_barrier_count,
+			)
+		};
+		let buffers = unsafe {
+			slice(
+				dependency_info.p_buffer_memory_barriers,
+				dependency_info.buffer_memory_barrier_count,
+			)
+		};
+		let images = unsafe {
+			slice(
+				dependency_info.p_image_memory_barriers,
+				dependency_info.image_memory_barrier_count,
+			)
+		};
+
+		*self.calls.borrow_mut() += 1;
+		self.dependency_flags
+			.borrow_mut()
+			.push(dependency_info.dependency_flags);
+		self.memory_barriers
+			.borrow_mut()
+			.extend(memory.iter().map(|b| (b.src_stage_mask, b.dst_stage_mask)));
+		self.buffer_barriers
+			.borrow_mut()
+			.extend(buffers.iter().map(|b| (b.src_stage_mask, b.dst_stage_mask)));
+		self.image_barriers
+			.borrow_mut()
+			.extend(images.iter().map(|b| (b.src_stage_mask, b.dst_stage_mask)));
+	}
+}
+
+impl vk_sync::cmd::Synchronization2 for Recorder {
+	unsafe fn cmd_pipeline_barrier2(
+		&self,
+		_command_buffer: vk::CommandBuffer,
+		dependency_info: &vk::DependencyInfo,
+	) {
+		self.record(dependency_info);
+	}
+
+	unsafe fn cmd_set_event2(
+		&self,
+		_command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+		dependency_info: &vk::DependencyInfo,
+	) {
+		self.events.borrow_mut().push(("set", event));
+		self.record(dependency_info);
+	}
+
+	unsafe fn cmd_reset_event2(
+		&self,
+		_command_buffer: vk::CommandBuffer,
+		event: vk::Event,
+		stage_mask: vk::PipelineStageFlags2,
+	) {
+		self.events.borrow_mut().push(("reset", event));
+		self.reset_stages.borrow_mut().push(stage_mask);
+	}
+
+	unsafe fn cmd_wait_events2(
+		&self,
+		_command_buffer: vk::CommandBuffer,
+		events: &[vk::Event],
+		dependency_infos: &[vk::DependencyInfo],
+	) {
+		for (event, dependency_info) in events.iter().zip(dependency_infos) {
+			self.events.borrow_mut().push(("wait", *event));
+			self.record(dependency_info);
+		}
+	}
+}
+
+#[test]
+fn pipeline_barrier2_keeps_stages_per_barrier() {
+	// Compute write to sampled image and an unrelated transfer buffer barrier in one call
+	let recorder = Recorder::default();
+
+	vk_sync::cmd::pipeline_barrier2(
+		&recorder,
+		vk::CommandBuffer::null(),
+		None,
+		&[vk_sync::BufferBarrier {
+			previous_accesses: &[vk_sync::AccessType::TransferWrite],
+			next_accesses: &[vk_sync::AccessType::TransferRead],
+			queue_transfer: vk_sync::QueueTransfer::None,
+			buffer: vk::Buffer::null(),
+			offset: 0,
+			size: 64,
+		}],
+		&[vk_sync::ImageBarrier {
+			previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+			next_accesses: &[
+				vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+			],
+			queue_transfer: vk_sync::QueueTransfer::None,
+			..Default::default()
+		}],
+	);
+
+	assert_eq!(*recorder.calls.borrow(), 1);
+	assert!(recorder.memory_barriers.borrow().is_empty());
+	assert_eq!(
+		*recorder.buffer_barriers.borrow(),
+		[(
+			vk::PipelineStageFlags2::ALL_TRANSFER,
+			vk::PipelineStageFlags2::ALL_TRANSFER
+		)]
+	);
+	assert_eq!(
+		*recorder.image_barriers.borrow(),
+		[(
+			vk::PipelineStageFlags2::COMPUTE_SHADER,
+			vk::PipelineStageFlags2::FRAGMENT_SHADER
+		)]
+	);
+}
+
+#[test]
+fn pipeline_barrier2_global_barrier() {
+	let recorder = Recorder::default();
+
+	vk_sync::cmd::pipeline_barrier2(
+		&recorder,
+		vk::CommandBuffer::null(),
+		Some(vk_sync::GlobalBarrier {
+			previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+			next_accesses: &[vk_sync::AccessType::IndirectBuffer],
+		}),
+		&[],
+		&[],
+	);
+
+	assert_eq!(*recorder.calls.borrow(), 1);
+	assert_eq!(
+		*recorder.memory_barriers.borrow(),
+		[(
+			vk::PipelineStageFlags2::COMPUTE_SHADER,
+			vk::PipelineStageFlags2::DRAW_INDIRECT
+		)]
+	);
+}
+
+#[test]
+fn pipeline_barrier2_with_options() {
+	// Framebuffer-local dependency between color attachment output and fragment reads,
+	// along with a depth read to depth write execution dependency
+	let recorder = Recorder::default();
+
+	vk_sync::cmd::pipeline_barrier2_with_options(
+		&recorder,
+		vk::CommandBuffer::null(),
+		vk_sync::cmd::BarrierOptions {
+			execution_barrier: Some(vk_sync::ExecutionBarrier {
+				previous_accesses: &[vk_sync::AccessType::DepthStencilAttachmentRead],
+				next_accesses: &[vk_sync::AccessType::DepthStencilAttachmentWrite],
+			}),
+			dependency_flags: vk::DependencyFlags::BY_REGION,
+		},
+		Some(vk_sync::GlobalBarrier {
+			previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+			next_accesses: &[vk_sync::AccessType::FragmentShaderReadColorInputAttachment],
+		}),
+		&[],
+		&[],
+	);
+
+	assert_eq!(*recorder.calls.borrow(), 1);
+	assert_eq!(
+		*recorder.dependency_flags.borrow(),
+		[vk::DependencyFlags::BY_REGION]
+	);
+	let depth = vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+		| vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS;
+	assert_eq!(
+		*recorder.memory_barriers.borrow(),
+		[
+			(depth, depth),
+			(
+				vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+				vk::PipelineStageFlags2::FRAGMENT_SHADER
+			)
+		]
+	);
+}
+
+#[test]
+fn pipeline_barrier2_many_barriers() {
+	// More barriers than fit inline still get recorded in a single call
+	let recorder = Recorder::default();
+	let buffer_barriers: Vec<vk_sync::BufferBarrier> = (0..40)
+		.map(|_| vk_sync::BufferBarrier {
+			previous_accesses: &[vk_sync::AccessType::TransferWrite],
+			next_accesses: &[vk_sync::AccessType::VertexBuffer],
+			queue_transfer: vk_sync::QueueTransfer::Transfer { src: 0, dst: 1 },
+			buffer: vk::Buffer::null(),
+			offset: 0,
+			size: 64,
+		})
+		.collect();
+
+	vk_sync::cmd::pipeline_barrier2(
+		&recorder,
+		vk::CommandBuffer::null(),
+		None,
+		&buffer_barriers,
+		&[],
+	);
+
+	assert_eq!(*recorder.calls.borrow(), 1);
+	assert_eq!(recorder.buffer_barriers.borrow().len(), 40);
+}
+
+#[test]
+fn pipeline_barrier2_skips_unrequired_barriers() {
+	// Read after read, nothing needs to be recorded
+	let recorder = Recorder::default();
+
+	vk_sync::cmd::pipeline_barrier2(
+		&recorder,
+		vk::CommandBuffer::null(),
+		Some(vk_sync::GlobalBarrier {
+			previous_accesses: &[vk_sync::AccessType::IndexBuffer],
+			next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		}),
+		&[],
+		&[],
+	);
+
+	assert_eq!(*recorder.calls.borrow(), 0);
+}
+
+#[test]
+fn split_barrier2_matching_dependency_info() {
+	// Compute write to storage image, with unrelated work before the fragment read
+	let recorder = Recorder::default();
+	let event = vk::Event::from_raw(1);
+	let image_barriers = [vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		previous_layout: vk_sync::ImageLayout::General,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		..Default::default()
+	}];
+
+	let split_barrier = vk_sync::cmd::SplitBarrier {
+		execution_barrier: Some(vk_sync::ExecutionBarrier {
+			previous_accesses: &[vk_sync::AccessType::TransferRead],
+			next_accesses: &[vk_sync::AccessType::TransferWrite],
+		}),
+		image_barriers: &image_barriers,
+		..Default::default()
+	};
+
+	let pending = split_barrier.begin2(&recorder, vk::CommandBuffer::null(), event);
+	assert_eq!(pending.event(), event);
+	pending.end2(&recorder, vk::CommandBuffer::null());
+
+	assert_eq!(*recorder.events.borrow(), [("set", event), ("wait", event)]);
+
+	// The same dependency is passed to both commands
+	let execution = (
+		vk::PipelineStageFlags2::ALL_TRANSFER,
+		vk::PipelineStageFlags2::ALL_TRANSFER,
+	);
+	assert_eq!(*recorder.memory_barriers.borrow(), [execution, execution]);
+	let image = (
+		vk::PipelineStageFlags2::COMPUTE_SHADER,
+		vk::PipelineStageFlags2::FRAGMENT_SHADER,
+	);
+	assert_eq!(*recorder.image_barriers.borrow(), [image, image]);
+}
+
+#[test]
+fn events2_dependency_info_per_event() {
+	// Two events set with the same compute to indirect draw dependency
+	let recorder = Recorder::default();
+	let events = [vk::Event::from_raw(1), vk::Event::from_raw(2)];
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::IndirectBuffer],
+	};
+
+	for event in events {
+		vk_sync::cmd::set_event2(
+			&recorder,
+			vk::CommandBuffer::null(),
+			event,
+			vk_sync::cmd::BarrierOptions::default(),
+			Some(global_barrier.clone()),
+			&[],
+			&[],
+		);
+	}
+	vk_sync::cmd::wait_events2(
+		&recorder,
+		vk::CommandBuffer::null(),
+		&events,
+		vk_sync::cmd::BarrierOptions::default(),
+		Some(global_barrier.clone()),
+		&[],
+		&[],
+	);
+
+	assert_eq!(
+		*recorder.events.borrow(),
+		[
+			("set", events[0]),
+			("set", events[1]),
+			("wait", events[0]),
+			("wait", events[1])
+		]
+	);
+	let stages = (
+		vk::PipelineStageFlags2::COMPUTE_SHADER,
+		vk::PipelineStageFlags2::DRAW_INDIRECT,
+	);
+	assert_eq!(*recorder.memory_barriers.borrow(), [stages; 4]);
+	// The event commands don't take any dependency flags
+	assert_eq!(
+		*recorder.dependency_flags.borrow(),
+		[vk::DependencyFlags::empty(); 4]
+	);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "vkCmdSetEvent2 doesn't take dependency flags")]
+fn set_event2_with_dependency_flags() {
+	vk_sync::cmd::set_event2(
+		&Recorder::default(),
+		vk::CommandBuffer::null(),
+		vk::Event::null(),
+		vk_sync::cmd::BarrierOptions {
+			dependency_flags: vk::DependencyFlags::BY_REGION,
+			..Default::default()
+		},
+		None,
+		&[],
+		&[],
+	);
+}
+
+#[test]
+fn reset_event2_stages() {
+	let recorder = Recorder::default();
+	let event = vk::Event::from_raw(1);
+
+	vk_sync::cmd::reset_event2(
+		&recorder,
+		vk::CommandBuffer::null(),
+		event,
+		&[vk_sync::AccessType::IndirectBuffer],
+	);
+	// Without previous accesses, nothing has to complete first
+	vk_sync::cmd::reset_event2(&recorder, vk::CommandBuffer::null(), event, &[]);
+
+	assert_eq!(
+		*recorder.events.borrow(),
+		[("reset", event), ("reset", event)]
+	);
+	assert_eq!(
+		*recorder.reset_stages.borrow(),
+		[
+			vk::PipelineStageFlags2::DRAW_INDIRECT,
+			vk::PipelineStageFlags2::NONE
+		]
+	);
+}
+
+thread_local! {
+	static LEGACY_COMMANDS: RefCell<Vec<(&'static str, vk::PipelineStageFlags)>> =
+		const { RefCell::new(Vec::new()) };
+}
+
+unsafe extern "system" fn cmd_set_event(
+	_command_buffer: vk::CommandBuffer,
+	_event: vk::Event,
+	stage_mask: vk::PipelineStageFlags,
+) {
+	LEGACY_COMMANDS.with(|commands| commands.borrow_mut().push(("set", stage_mask)));
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "system" fn cmd_wait_events(
+	_command_buffer: vk::CommandBuffer,
+	_event_count: u32,
+	_p_events: *const vk::Event,
+	src_stage_mask: vk::PipelineStageFlags,
+	_dst_stage_mask: vk::PipelineStageFlags,
+	_memory_barrier_count: u32,
+	_p_memory_barriers: *const vk::MemoryBarrier,
+	_buffer_memory_barrier_count: u32,
+	_p_buffer_memory_barriers: *const vk::BufferMemoryBarrier,
+	_image_memory_barrier_count: u32,
+	_p_image_memory_barriers: *const vk::ImageMemoryBarrier,
+) {
+	LEGACY_COMMANDS.with(|commands| commands.borrow_mut().push(("wait", src_stage_mask)));
+}
+
+#[test]
+fn split_barrier_matching_stage_masks() {
+	// Device with only the event commands loaded
+	let device = common::load_device(&[
+		(c"vkCmdSetEvent", cmd_set_event as *const c_void),
+		(c"vkCmdWaitEvents", cmd_wait_events as *const c_void),
+	]);
+
+	let buffer_barriers = [vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		queue_transfer: vk_sync::QueueTransfer::Transfer { src: 0, dst: 1 },
+		size: 64,
+		..Default::default()
+	}];
+	let split_barrier = vk_sync::cmd::SplitBarrier {
+		global_barrier: Some(vk_sync::GlobalBarrier {
+			previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+			next_accesses: &[vk_sync::AccessType::IndirectBuffer],
+		}),
+		buffer_barriers: &buffer_barriers,
+		..Default::default()
+	};
+
+	split_barrier
+		.begin(&device, vk::CommandBuffer::null(), vk::Event::null())
+		.end(&device, vk::CommandBuffer::null());
+
+	let stages = vk::PipelineStageFlags::TOP_OF_PIPE
+		| vk::PipelineStageFlags::COMPUTE_SHADER
+		| vk::PipelineStageFlags::TRANSFER;
+	LEGACY_COMMANDS.with(|commands| {
+		assert_eq!(*commands.borrow(), [("set", stages), ("wait", stages)]);
+	});
+}
+
+thread_local! {
+	static LEGACY_BARRIERS: RefCell<Vec<(vk::PipelineStageFlags, vk::PipelineStageFlags, vk::DependencyFlags)>> =
+		const { RefCell::new(Vec::new()) };
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "system" fn cmd_pipeline_barrier(
+	_command_buffer: vk::CommandBuffer,
+	src_stage_mask: vk::PipelineStageFlags,
+	dst_stage_mask: vk::PipelineStageFlags,
+	dependency_flags: vk::DependencyFlags,
+	memory_barrier_count: u32,
+	_p_memory_barriers: *const vk::MemoryBarrier,
+	buffer_memory_barrier_count: u32,
+	_p_buffer_memory_barriers: *const vk::BufferMemoryBarrier,
+	image_memory_barrier_count: u32,
+	_p_image_memory_barriers: *const vk::ImageMemoryBarrier,
+) {
+	assert_eq!(
+		memory_barrier_count + buffer_memory_barrier_count + image_memory_barrier_count,
+		0
+	);
+	LEGACY_BARRIERS.with(|barriers| {
+		barriers
+			.borrow_mut()
+			.push((src_stage_mask, dst_stage_mask, dependency_flags))
+	});
+}
+
+#[test]
+fn pipeline_barrier_with_execution_barrier() {
+	// Compute read from storage buffer, Compute write from storage buffer (WAR hazard)
+	let device = common::load_device(&[(
+		c"vkCmdPipelineBarrier",
+		cmd_pipeline_barrier as *const c_void,
+	)]);
+
+	vk_sync::cmd::pipeline_barrier_with_options(
+		&device,
+		vk::CommandBuffer::null(),
+		vk_sync::cmd::BarrierOptions {
+			execution_barrier: Some(vk_sync::ExecutionBarrier {
+				previous_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+				next_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+			}),
+			dependency_flags: vk::DependencyFlags::VIEW_LOCAL,
+		},
+		None,
+		&[],
+		&[],
+	);
+
+	// Read to read doesn't need a barrier
+	vk_sync::cmd::pipeline_barrier(
+		&device,
+		vk::CommandBuffer::null(),
+		Some(vk_sync::GlobalBarrier {
+			previous_accesses: &[vk_sync::AccessType::IndexBuffer],
+			next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		}),
+		&[],
+		&[],
+	);
+
+	let stages = vk::PipelineStageFlags::COMPUTE_SHADER;
+	LEGACY_BARRIERS.with(|barriers| {
+		assert_eq!(
+			*barriers.borrow(),
+			[(
+				vk::PipelineStageFlags::TOP_OF_PIPE | stages,
+				vk::PipelineStageFlags::BOTTOM_OF_PIPE | stages,
+				vk::DependencyFlags::VIEW_LOCAL
+			)]
+		);
+	});
+}
+
+#[test]
+fn batch_flush_with_options() {
+	// Nothing pushed, but the execution barrier still has to be recorded
+	let device = common::load_device(&[(
+		c"vkCmdPipelineBarrier",
+		cmd_pipeline_barrier as *const c_void,
+	)]);
+	let mut batch = vk_sync::cmd::BarrierBatch::new();
+
+	batch.flush(&device, vk::CommandBuffer::null());
+	batch.flush_with_options(
+		&device,
+		vk::CommandBuffer::null(),
+		vk_sync::cmd::BarrierOptions {
+			execution_barrier: Some(vk_sync::ExecutionBarrier {
+				previous_accesses: &[vk_sync::AccessType::TransferRead],
+				next_accesses: &[vk_sync::AccessType::TransferWrite],
+			}),
+			dependency_flags: vk::DependencyFlags::DEVICE_GROUP,
+		},
+	);
+	assert!(batch.is_empty());
+
+	let stages = vk::PipelineStageFlags::TRANSFER;
+	LEGACY_BARRIERS.with(|barriers| {
+		assert_eq!(
+			*barriers.borrow(),
+			[(stages, stages, vk::DependencyFlags::DEVICE_GROUP)]
+		);
+	});
+}
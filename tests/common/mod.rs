@@ -0,0 +1,71 @@
+//! Fixtures and mock devices shared by the integration tests.
+
+use ash::vk;
+use std::ffi::{CStr, c_void};
+
+/// The first mip level and array layer of `aspect_mask`.
+#[allow(dead_code)]
+pub fn range(aspect_mask: vk::ImageAspectFlags) -> vk::ImageSubresourceRange {
+	vk::ImageSubresourceRange {
+		aspect_mask,
+		base_mip_level: 0,
+		level_count: 1,
+		base_array_layer: 0,
+		layer_count: 1,
+	}
+}
+
+#[allow(dead_code)]
+pub fn color_range() -> vk::ImageSubresourceRange {
+	range(vk::ImageAspectFlags::COLOR)
+}
+
+/// Loads a device without a Vulkan driver, with only the given `commands`
+/// pointing at stub functions - every other command is null.
+#[allow(dead_code)]
+pub fn load_device(commands: &[(&CStr, *const c_void)]) -> ash::Device {
+	let load = |name: &CStr| -> *const c_void {
+		commands
+			.iter()
+			.find(|(command, _)| *command == name)
+			.map_or(std::ptr::null(), |(_, function)| *function)
+	};
+	unsafe { ash::Device::load_with(load, vk::Device::null()) }
+}
+
+/// A synchronization2 device that ignores every command.
+#[allow(dead_code)]
+pub struct NullDevice;
+
+impl vk_sync::cmd::Synchronization2 for NullDevice {
+	unsafe fn cmd_pipeline_barrier2(
+		&self,
+		_command_buffer: vk::CommandBuffer,
+		_dependency_info: &vk::DependencyInfo,
+	) {
+	}
+
+	unsafe fn cmd_set_event2(
+		&self,
+		_command_buffer: vk::CommandBuffer,
+		_event: vk::Event,
+		_dependency_info: &vk::DependencyInfo,
+	) {
+	}
+
+	unsafe fn cmd_reset_event2(
+		&self,
+		_command_buffer: vk::CommandBuffer,
+		_event: vk::Event,
+		_stage_mask: vk::PipelineStageFlags2,
+	) {
+	}
+
+	unsafe fn cmd_wait_events2(
+		&self,
+		_command_buffer: vk::CommandBuffer,
+		_events: &[vk::Event],
+		_dependency_infos: &[vk::DependencyInfo],
+	) {
+	}
+}
@@ -0,0 +1,178 @@
+//! Invalid barrier definitions, which the fallible mapping functions report as errors.
+
+mod common;
+
+use ash::vk;
+use common::color_range;
+
+#[test]
+fn conflicting_next_layouts() {
+	// Reading as a color attachment and a sampled image requires two different layouts
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[
+			vk_sync::AccessType::ColorAttachmentRead,
+			vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+		],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: color_range(),
+	};
+
+	let error = vk_sync::try_get_image_memory_barrier(&image_barrier).unwrap_err();
+
+	assert_eq!(
+		error,
+		vk_sync::BarrierError::ConflictingLayouts {
+			first: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+			second: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+		}
+	);
+}
+
+#[test]
+fn general_layout_has_no_conflicts() {
+	// The same accesses are fine when the image stays in the general layout
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[
+			vk_sync::AccessType::ColorAttachmentRead,
+			vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+		],
+		previous_layout: vk_sync::ImageLayout::General,
+		next_layout: vk_sync::ImageLayout::General,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: color_range(),
+	};
+
+	let (_, _, barrier) = vk_sync::try_get_image_memory_barrier(&image_barrier).unwrap();
+
+	assert_eq!(barrier.old_layout, vk::ImageLayout::GENERAL);
+	assert_eq!(barrier.new_layout, vk::ImageLayout::GENERAL);
+}
+
+#[test]
+fn multiple_write_accesses() {
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[
+			vk_sync::AccessType::ComputeShaderWrite,
+			vk_sync::AccessType::TransferWrite,
+		],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+	};
+
+	let error = vk_sync::try_get_memory_barrier(&global_barrier).unwrap_err();
+
+	assert_eq!(
+		error,
+		vk_sync::BarrierError::MultipleWriteAccesses {
+			first: vk_sync::AccessType::ComputeShaderWrite,
+			second: vk_sync::AccessType::TransferWrite,
+		}
+	);
+}
+
+#[test]
+fn write_mixed_with_reads() {
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[
+			vk_sync::AccessType::TransferRead,
+			vk_sync::AccessType::TransferWrite,
+		],
+	};
+
+	let error = vk_sync::try_get_memory_barrier(&global_barrier).unwrap_err();
+
+	assert_eq!(
+		error,
+		vk_sync::BarrierError::WriteMixedWithReads {
+			write: vk_sync::AccessType::TransferWrite,
+			read: vk_sync::AccessType::TransferRead,
+		}
+	);
+}
+
+#[test]
+fn image_only_access_on_buffer() {
+	let buffer_barrier = vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[vk_sync::AccessType::TransferRead],
+		queue_transfer: vk_sync::QueueTransfer::None,
+		buffer: vk::Buffer::null(),
+		offset: 0,
+		size: 64,
+	};
+
+	let error = vk_sync::try_get_buffer_memory_barrier(&buffer_barrier).unwrap_err();
+
+	assert_eq!(
+		error,
+		vk_sync::BarrierError::ImageOnlyAccessOnBuffer(vk_sync::AccessType::ColorAttachmentWrite)
+	);
+}
+
+#[test]
+fn present_in_global_barrier() {
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[vk_sync::AccessType::Present],
+	};
+
+	let error = vk_sync::try_get_memory_barrier(&global_barrier).unwrap_err();
+
+	assert_eq!(error, vk_sync::BarrierError::PresentInGlobalBarrier);
+}
+
+#[test]
+fn infallible_mapping_is_permissive() {
+	// The infallible mapping functions translate invalid definitions as they are,
+	// with the last access determining the image layout
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[
+			vk_sync::AccessType::ColorAttachmentRead,
+			vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+		],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: color_range(),
+	};
+
+	let (_, dst_mask, barrier) = vk_sync::get_image_memory_barrier(&image_barrier);
+
+	assert_eq!(
+		dst_mask,
+		vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::FRAGMENT_SHADER
+	);
+	assert_eq!(
+		barrier.old_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+	);
+
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[vk_sync::AccessType::Present],
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_memory_barrier(&global_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::BOTTOM_OF_PIPE);
+	assert_eq!(
+		barrier.src_access_mask,
+		vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+	);
+}
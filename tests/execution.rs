@@ -0,0 +1,51 @@
+//! Execution only dependencies, which only need the stage masks of a barrier.
+
+use ash::vk;
+
+#[test]
+fn compute_read_storage_compute_write_storage() {
+	// Compute read from storage buffer, Compute write from storage buffer (WAR hazard)
+	let execution_barrier = vk_sync::ExecutionBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+	};
+
+	let (src_mask, dst_mask) = vk_sync::get_execution_barrier(&execution_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::COMPUTE_SHADER);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::COMPUTE_SHADER);
+}
+
+#[test]
+fn graphics_read_sampled_transfer_write() {
+	// Graphics fragment read from sampled image, Transfer write over the same image
+	let execution_barrier = vk_sync::ExecutionBarrier {
+		previous_accesses: &[
+			vk_sync::AccessType::VertexShaderReadSampledImageOrUniformTexelBuffer,
+			vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+		],
+		next_accesses: &[vk_sync::AccessType::TransferWrite],
+	};
+
+	let (src_mask, dst_mask) = vk_sync::get_execution_barrier(&execution_barrier);
+
+	assert_eq!(
+		src_mask,
+		vk::PipelineStageFlags::VERTEX_SHADER | vk::PipelineStageFlags::FRAGMENT_SHADER
+	);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::TRANSFER);
+}
+
+#[test]
+fn nothing_nothing() {
+	// No accesses on either side, so the stage masks fall back to valid defaults
+	let execution_barrier = vk_sync::ExecutionBarrier {
+		previous_accesses: &[vk_sync::AccessType::Nothing],
+		next_accesses: &[],
+	};
+
+	let (src_mask, dst_mask) = vk_sync::get_execution_barrier(&execution_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::TOP_OF_PIPE);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::BOTTOM_OF_PIPE);
+}
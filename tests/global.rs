@@ -175,3 +175,85 @@ fn compute_write_texel_graphics_read_indirect_fragment_read_uniform() {
 		vk::AccessFlags::INDIRECT_COMMAND_READ | vk::AccessFlags::UNIFORM_READ
 	);
 }
+
+#[test]
+fn compute_write_storage_task_read_storage() {
+	// Compute write to storage buffer, Task shader read from storage buffer
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::TaskShaderReadOther],
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_memory_barrier(&global_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::COMPUTE_SHADER);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::TASK_SHADER_EXT);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::SHADER_WRITE);
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags::SHADER_READ);
+}
+
+#[test]
+fn mesh_write_storage_fragment_read_uniform() {
+	// Mesh shader write to storage buffer, Graphics fragment read as uniform buffer
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::MeshShaderWrite],
+		next_accesses: &[
+			vk_sync::AccessType::MeshShaderReadUniformBuffer,
+			vk_sync::AccessType::FragmentShaderReadUniformBuffer,
+		],
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_memory_barrier(&global_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::MESH_SHADER_EXT);
+	assert_eq!(
+		dst_mask,
+		vk::PipelineStageFlags::MESH_SHADER_EXT | vk::PipelineStageFlags::FRAGMENT_SHADER
+	);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::SHADER_WRITE);
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags::UNIFORM_READ);
+}
+
+#[test]
+fn build_acceleration_structure_ray_tracing_read() {
+	// Acceleration structure build, Ray tracing shader read as acceleration structure
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::AccelerationStructureBuildWrite],
+		next_accesses: &[vk_sync::AccessType::RayTracingShaderReadAccelerationStructure],
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_memory_barrier(&global_barrier);
+
+	assert_eq!(
+		src_mask,
+		vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR
+	);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR);
+	assert_eq!(
+		barrier.src_access_mask,
+		vk::AccessFlags::ACCELERATION_STRUCTURE_WRITE_KHR
+	);
+	assert_eq!(
+		barrier.dst_access_mask,
+		vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR
+	);
+}
+
+#[test]
+fn transfer_write_acceleration_structure_build_input() {
+	// Upload of vertex data, Acceleration structure build reading the geometry
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[vk_sync::AccessType::AccelerationStructureBuildInputRead],
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_memory_barrier(&global_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::TRANSFER);
+	assert_eq!(
+		dst_mask,
+		vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR
+	);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::TRANSFER_WRITE);
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags::SHADER_READ);
+}
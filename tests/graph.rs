@@ -0,0 +1,201 @@
+#![cfg(feature = "graph")]
+
+mod common;
+
+use ash::vk;
+use ash::vk::Handle;
+use common::color_range;
+use std::cell::RefCell;
+use vk_sync::graph::{GraphError, Pass, RenderGraph};
+
+#[test]
+fn color_attachment_then_sampled() {
+	// Render to an image, then sample it in a later pass
+	let image = vk::Image::from_raw(1);
+	let mut graph = RenderGraph::new();
+	graph.import_image(image, color_range(), vk_sync::ImageLayout::Optimal, &[]);
+
+	let render = graph.add_pass(Pass::new("render").image(
+		image,
+		&[vk_sync::AccessType::ColorAttachmentWrite],
+		vk_sync::ImageLayout::Optimal,
+	));
+	let sample = graph.add_pass(Pass::new("sample").image(
+		image,
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		vk_sync::ImageLayout::Optimal,
+	));
+
+	let compiled = graph.compile().unwrap();
+	assert_eq!(compiled.order(), [render, sample]);
+
+	let barriers = compiled.barriers(render);
+	assert_eq!(barriers.image_barriers.len(), 1);
+	let (_, _, barrier) = vk_sync::get_image_memory_barrier(&barriers.image_barriers[0]);
+	assert_eq!(barrier.old_layout, vk::ImageLayout::UNDEFINED);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+
+	let barriers = compiled.barriers(sample);
+	assert_eq!(barriers.image_barriers.len(), 1);
+	let (src_mask, dst_mask, barrier) =
+		vk_sync::get_image_memory_barrier(&barriers.image_barriers[0]);
+	assert_eq!(src_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::FRAGMENT_SHADER);
+	assert_eq!(
+		barrier.old_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+	);
+}
+
+#[test]
+fn compute_write_then_indirect_draw() {
+	// Compute writes draw arguments, consumed by an indirect draw
+	let arguments = vk::Buffer::from_raw(1);
+	let mut graph = RenderGraph::new();
+
+	let cull = graph
+		.add_pass(Pass::new("cull").buffer(arguments, &[vk_sync::AccessType::ComputeShaderWrite]));
+	let draw =
+		graph.add_pass(Pass::new("draw").buffer(arguments, &[vk_sync::AccessType::IndirectBuffer]));
+
+	let compiled = graph.compile().unwrap();
+	assert!(compiled.barriers(cull).is_empty());
+
+	let barriers = compiled.barriers(draw);
+	let (src_mask, dst_mask, barrier) = vk_sync::get_memory_barrier(&barriers.global_barriers[0]);
+	assert_eq!(src_mask, vk::PipelineStageFlags::COMPUTE_SHADER);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::DRAW_INDIRECT);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::SHADER_WRITE);
+	assert_eq!(
+		barrier.dst_access_mask,
+		vk::AccessFlags::INDIRECT_COMMAND_READ
+	);
+}
+
+#[test]
+fn explicit_dependency_reorders_passes() {
+	let buffer = vk::Buffer::from_raw(1);
+	let mut graph = RenderGraph::new();
+
+	let upload =
+		graph.add_pass(Pass::new("upload").buffer(buffer, &[vk_sync::AccessType::TransferWrite]));
+	let unrelated = graph.add_pass(Pass::new("unrelated"));
+	let read =
+		graph.add_pass(Pass::new("read").buffer(buffer, &[vk_sync::AccessType::VertexBuffer]));
+	graph.add_dependency(read, unrelated);
+
+	let compiled = graph.compile().unwrap();
+	assert_eq!(compiled.order(), [upload, read, unrelated]);
+	assert_eq!(compiled.name(unrelated), "unrelated");
+}
+
+#[test]
+fn cycle() {
+	let buffer = vk::Buffer::from_raw(1);
+	let mut graph = RenderGraph::new();
+
+	let write =
+		graph.add_pass(Pass::new("write").buffer(buffer, &[vk_sync::AccessType::TransferWrite]));
+	let read =
+		graph.add_pass(Pass::new("read").buffer(buffer, &[vk_sync::AccessType::TransferRead]));
+	graph.add_dependency(read, write);
+
+	assert_eq!(graph.compile().err(), Some(GraphError::Cycle));
+}
+
+#[test]
+fn unknown_image() {
+	let image = vk::Image::from_raw(1);
+	let mut graph = RenderGraph::new();
+	graph.add_pass(Pass::new("clear").image(
+		image,
+		&[vk_sync::AccessType::ClearWrite],
+		vk_sync::ImageLayout::Optimal,
+	));
+
+	assert_eq!(graph.compile().err(), Some(GraphError::UnknownImage(image)));
+}
+
+#[test]
+fn execute_calls_passes_in_order() {
+	// Read only passes need no barriers, so nothing is recorded on the device
+	let buffer = vk::Buffer::from_raw(1);
+	let executed = RefCell::new(Vec::new());
+	let device = common::load_device(&[]);
+
+	let mut graph = RenderGraph::new();
+	graph.import_buffer(buffer, &[vk_sync::AccessType::VertexBuffer]);
+	for name in ["first", "second", "third"] {
+		let executed = &executed;
+		graph.add_pass(
+			Pass::new(name)
+				.buffer(buffer, &[vk_sync::AccessType::VertexBuffer])
+				.execute(move |_, _| executed.borrow_mut().push(name)),
+		);
+	}
+
+	let mut compiled = graph.compile().unwrap();
+	compiled.execute(&device, vk::CommandBuffer::null());
+
+	assert_eq!(*executed.borrow(), ["first", "second", "third"]);
+}
+
+#[test]
+fn aliased_images() {
+	// Two transient images sharing memory
+	let first = vk::Image::from_raw(1);
+	let second = vk::Image::from_raw(2);
+	let output = vk::Image::from_raw(3);
+	let mut graph = RenderGraph::new();
+	graph.import_image(first, color_range(), vk_sync::ImageLayout::Optimal, &[]);
+	graph.import_image(second, color_range(), vk_sync::ImageLayout::Optimal, &[]);
+	graph.import_image(output, color_range(), vk_sync::ImageLayout::Optimal, &[]);
+	graph.add_alias(first, second);
+
+	// Added out of order, so only the alias can order the passes
+	let use_second = graph.add_pass(
+		Pass::new("use second")
+			.image(
+				second,
+				&[vk_sync::AccessType::ColorAttachmentWrite],
+				vk_sync::ImageLayout::Optimal,
+			)
+			.image(
+				output,
+				&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+				vk_sync::ImageLayout::Optimal,
+			),
+	);
+	let use_first = graph.add_pass(Pass::new("use first").image(
+		first,
+		&[vk_sync::AccessType::ComputeShaderWrite],
+		vk_sync::ImageLayout::General,
+	));
+
+	let compiled = graph.compile().unwrap();
+	assert_eq!(compiled.order(), [use_first, use_second]);
+
+	let barriers = compiled.barriers(use_second);
+	let image_barrier = barriers
+		.image_barriers
+		.iter()
+		.find(|barrier| barrier.image == second)
+		.unwrap();
+	assert!(image_barrier.discard_contents);
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_image_memory_barrier(image_barrier);
+	assert_eq!(src_mask, vk::PipelineStageFlags::COMPUTE_SHADER);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(barrier.old_layout, vk::ImageLayout::UNDEFINED);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+}
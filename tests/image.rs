@@ -11,8 +11,7 @@ fn compute_write_storage_fragment_read_sampled() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -45,8 +44,7 @@ fn graphics_write_color_compute_read_sampled() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -85,8 +83,7 @@ fn graphics_write_depth_compute_read_sampled() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -128,8 +125,7 @@ fn graphics_write_depth_fragment_read_attachment() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -174,8 +170,7 @@ fn graphics_write_depth_fragment_read_sampled() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -217,8 +212,7 @@ fn graphics_write_color_fragment_read_attachment() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -260,8 +254,7 @@ fn graphics_write_color_fragment_read_sampled() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -300,8 +293,7 @@ fn graphics_write_color_vertex_read_sampled() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -342,8 +334,7 @@ fn graphics_read_sampled_graphics_write_color() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -379,8 +370,7 @@ fn transfer_write_image_fragment_read_sampled() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -413,8 +403,7 @@ fn graphics_write_color_presentation() {
 		previous_layout: vk_sync::ImageLayout::Optimal,
 		next_layout: vk_sync::ImageLayout::Optimal,
 		discard_contents: false,
-		src_queue_family_index: 0,
-		dst_queue_family_index: 0,
+		queue_transfer: vk_sync::QueueTransfer::None,
 		image: vk::Image::null(),
 		range: vk::ImageSubresourceRange {
 			aspect_mask: vk::ImageAspectFlags::empty(),
@@ -440,3 +429,99 @@ fn graphics_write_color_presentation() {
 	);
 	assert_eq!(barrier.new_layout, vk::ImageLayout::PRESENT_SRC_KHR);
 }
+
+#[test]
+fn shared_present_initial_transition() {
+	// Single-buffered swapchain: transition a freshly acquired shared presentable image
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::Nothing],
+		next_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::GeneralAndPresentation,
+		discard_contents: true,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level: 0,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: 1,
+		},
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_image_memory_barrier(&image_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::TOP_OF_PIPE);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::empty());
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags::empty());
+	assert_eq!(barrier.old_layout, vk::ImageLayout::UNDEFINED);
+	assert_eq!(barrier.new_layout, vk::ImageLayout::SHARED_PRESENT_KHR);
+}
+
+#[test]
+fn shared_present_graphics_write_color_presentation() {
+	// Single-buffered swapchain: color attachment write, Presentation
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[vk_sync::AccessType::Present],
+		previous_layout: vk_sync::ImageLayout::GeneralAndPresentation,
+		next_layout: vk_sync::ImageLayout::GeneralAndPresentation,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level: 0,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: 1,
+		},
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_image_memory_barrier(&image_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::BOTTOM_OF_PIPE);
+	assert_eq!(
+		barrier.src_access_mask,
+		vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+	);
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags::empty());
+	assert_eq!(barrier.old_layout, vk::ImageLayout::SHARED_PRESENT_KHR);
+	assert_eq!(barrier.new_layout, vk::ImageLayout::SHARED_PRESENT_KHR);
+}
+
+#[test]
+fn shared_present_presentation_graphics_write_color() {
+	// Single-buffered swapchain: Presentation, color attachment write for the next frame
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::Present],
+		next_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		previous_layout: vk_sync::ImageLayout::GeneralAndPresentation,
+		next_layout: vk_sync::ImageLayout::GeneralAndPresentation,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level: 0,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: 1,
+		},
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_image_memory_barrier(&image_barrier);
+	let barrier2 = vk_sync::get_image_memory_barrier2(&image_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::TOP_OF_PIPE);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::empty());
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags::empty());
+	assert_eq!(barrier.old_layout, vk::ImageLayout::SHARED_PRESENT_KHR);
+	assert_eq!(barrier.new_layout, vk::ImageLayout::SHARED_PRESENT_KHR);
+	assert_eq!(barrier2.old_layout, vk::ImageLayout::SHARED_PRESENT_KHR);
+	assert_eq!(barrier2.new_layout, vk::ImageLayout::SHARED_PRESENT_KHR);
+}
@@ -0,0 +1,243 @@
+use ash::vk;
+
+const GRAPHICS: u32 = 0;
+const COMPUTE: u32 = 1;
+const TRANSFER: u32 = 2;
+
+#[test]
+fn graphics_write_image_compute_read() {
+	// Graphics renders to an image, a compute queue samples it
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderReadSampledImageOrUniformTexelBuffer],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::Transfer {
+			src: GRAPHICS,
+			dst: COMPUTE,
+		},
+		image: vk::Image::null(),
+		range: vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level: 0,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: 1,
+		},
+	};
+
+	let ((release_src, release_dst, release), (acquire_src, acquire_dst, acquire)) =
+		vk_sync::ownership_transfer_image(&image_barrier);
+
+	assert_eq!(release_src, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(release_dst, vk::PipelineStageFlags::BOTTOM_OF_PIPE);
+	assert_eq!(
+		release.src_access_mask,
+		vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+	);
+	assert_eq!(release.dst_access_mask, vk::AccessFlags::empty());
+
+	assert_eq!(acquire_src, vk::PipelineStageFlags::TOP_OF_PIPE);
+	assert_eq!(acquire_dst, vk::PipelineStageFlags::COMPUTE_SHADER);
+	assert_eq!(acquire.src_access_mask, vk::AccessFlags::empty());
+	assert_eq!(acquire.dst_access_mask, vk::AccessFlags::SHADER_READ);
+
+	// Both halves define the same transfer and layout transition
+	for barrier in &[release, acquire] {
+		assert_eq!(barrier.src_queue_family_index, GRAPHICS);
+		assert_eq!(barrier.dst_queue_family_index, COMPUTE);
+		assert_eq!(
+			barrier.old_layout,
+			vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+		);
+		assert_eq!(
+			barrier.new_layout,
+			vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+		);
+	}
+}
+
+#[test]
+fn compute_write_buffer_transfer_read() {
+	// Compute writes to a storage buffer, a transfer queue copies it back
+	let buffer_barrier = vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::TransferRead],
+		queue_transfer: vk_sync::QueueTransfer::Transfer {
+			src: COMPUTE,
+			dst: TRANSFER,
+		},
+		buffer: vk::Buffer::null(),
+		offset: 0,
+		size: vk::WHOLE_SIZE as usize,
+	};
+
+	let ((release_src, release_dst, release), (acquire_src, acquire_dst, acquire)) =
+		vk_sync::ownership_transfer_buffer(&buffer_barrier);
+
+	assert_eq!(release_src, vk::PipelineStageFlags::COMPUTE_SHADER);
+	assert_eq!(release_dst, vk::PipelineStageFlags::BOTTOM_OF_PIPE);
+	assert_eq!(release.src_access_mask, vk::AccessFlags::SHADER_WRITE);
+	assert_eq!(release.dst_access_mask, vk::AccessFlags::empty());
+
+	assert_eq!(acquire_src, vk::PipelineStageFlags::TOP_OF_PIPE);
+	assert_eq!(acquire_dst, vk::PipelineStageFlags::TRANSFER);
+	assert_eq!(acquire.src_access_mask, vk::AccessFlags::empty());
+	assert_eq!(acquire.dst_access_mask, vk::AccessFlags::TRANSFER_READ);
+
+	for barrier in &[release, acquire] {
+		assert_eq!(barrier.src_queue_family_index, COMPUTE);
+		assert_eq!(barrier.dst_queue_family_index, TRANSFER);
+		assert_eq!(barrier.size, vk::WHOLE_SIZE);
+	}
+}
+
+#[test]
+fn compute_write_buffer_transfer_read2() {
+	let buffer_barrier = vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::TransferRead],
+		queue_transfer: vk_sync::QueueTransfer::Transfer {
+			src: COMPUTE,
+			dst: TRANSFER,
+		},
+		buffer: vk::Buffer::null(),
+		offset: 0,
+		size: vk::WHOLE_SIZE as usize,
+	};
+
+	let (release, acquire) = vk_sync::ownership_transfer_buffer2(&buffer_barrier);
+
+	assert_eq!(
+		release.src_stage_mask,
+		vk::PipelineStageFlags2::COMPUTE_SHADER
+	);
+	assert_eq!(
+		release.src_access_mask,
+		vk::AccessFlags2::SHADER_STORAGE_WRITE
+	);
+	assert_eq!(release.dst_stage_mask, vk::PipelineStageFlags2::NONE);
+	assert_eq!(release.dst_access_mask, vk::AccessFlags2::NONE);
+
+	assert_eq!(acquire.src_stage_mask, vk::PipelineStageFlags2::NONE);
+	assert_eq!(acquire.src_access_mask, vk::AccessFlags2::NONE);
+	assert_eq!(acquire.dst_stage_mask, vk::PipelineStageFlags2::TRANSFER);
+	assert_eq!(acquire.dst_access_mask, vk::AccessFlags2::TRANSFER_READ);
+
+	for barrier in &[release, acquire] {
+		assert_eq!(barrier.src_queue_family_index, COMPUTE);
+		assert_eq!(barrier.dst_queue_family_index, TRANSFER);
+	}
+}
+
+#[test]
+fn graphics_write_image_compute_read2() {
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderReadSampledImageOrUniformTexelBuffer],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::Transfer {
+			src: GRAPHICS,
+			dst: COMPUTE,
+		},
+		image: vk::Image::null(),
+		range: vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level: 0,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: 1,
+		},
+	};
+
+	let (release, acquire) = vk_sync::ownership_transfer_image2(&image_barrier);
+
+	assert_eq!(
+		release.src_access_mask,
+		vk::AccessFlags2::COLOR_ATTACHMENT_WRITE
+	);
+	assert_eq!(release.dst_stage_mask, vk::PipelineStageFlags2::NONE);
+	assert_eq!(acquire.src_stage_mask, vk::PipelineStageFlags2::NONE);
+	assert_eq!(
+		acquire.dst_access_mask,
+		vk::AccessFlags2::SHADER_SAMPLED_READ
+	);
+
+	for barrier in &[release, acquire] {
+		assert_eq!(
+			barrier.old_layout,
+			vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+		);
+		assert_eq!(
+			barrier.new_layout,
+			vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+		);
+	}
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "doesn't define a queue family ownership transfer")]
+fn ownership_transfer_without_queue_transfer() {
+	let buffer_barrier = vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		..Default::default()
+	};
+
+	let _ = vk_sync::ownership_transfer_buffer(&buffer_barrier);
+}
+
+#[test]
+fn default_barrier_has_no_queue_transfer() {
+	// Default barriers must not transfer ownership to queue family 0
+	let buffer_barrier = vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		..Default::default()
+	};
+
+	let (_, _, barrier) = vk_sync::get_buffer_memory_barrier(&buffer_barrier);
+
+	assert_eq!(barrier.src_queue_family_index, vk::QUEUE_FAMILY_IGNORED);
+	assert_eq!(barrier.dst_queue_family_index, vk::QUEUE_FAMILY_IGNORED);
+	assert!(!buffer_barrier.queue_transfer.is_transfer());
+}
+
+#[test]
+fn external_and_foreign_queue_families() {
+	assert_eq!(
+		vk_sync::QueueTransfer::External(vk_sync::ExternalTransfer::Release { src: GRAPHICS })
+			.queue_family_indices(),
+		(GRAPHICS, vk::QUEUE_FAMILY_EXTERNAL)
+	);
+	assert_eq!(
+		vk_sync::QueueTransfer::External(vk_sync::ExternalTransfer::Acquire { dst: COMPUTE })
+			.queue_family_indices(),
+		(vk::QUEUE_FAMILY_EXTERNAL, COMPUTE)
+	);
+	assert_eq!(
+		vk_sync::QueueTransfer::Foreign(vk_sync::ExternalTransfer::Acquire { dst: TRANSFER })
+			.queue_family_indices(),
+		(vk::QUEUE_FAMILY_FOREIGN_EXT, TRANSFER)
+	);
+
+	// Acquiring an image exported to another API
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::Nothing],
+		next_accesses: &[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		next_layout: vk_sync::ImageLayout::Optimal,
+		queue_transfer: vk_sync::QueueTransfer::Foreign(vk_sync::ExternalTransfer::Acquire {
+			dst: GRAPHICS,
+		}),
+		..Default::default()
+	};
+
+	let (_, _, barrier) = vk_sync::get_image_memory_barrier(&image_barrier);
+
+	assert_eq!(barrier.src_queue_family_index, vk::QUEUE_FAMILY_FOREIGN_EXT);
+	assert_eq!(barrier.dst_queue_family_index, GRAPHICS);
+}
@@ -0,0 +1,160 @@
+use ash::vk;
+use vk_sync::render_pass::{
+	get_attachment_description, get_attachment_reference, get_subpass_dependency,
+	get_subpass_dependency2,
+};
+
+#[test]
+fn color_attachment_then_input_attachment() {
+	// Subpass 0 writes a color attachment, read as an input attachment by subpass 1
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+		next_accesses: &[vk_sync::AccessType::FragmentShaderReadColorInputAttachment],
+	};
+
+	let dependency = get_subpass_dependency(0, 1, &global_barrier)
+		.dependency_flags(vk::DependencyFlags::BY_REGION);
+
+	assert_eq!(dependency.src_subpass, 0);
+	assert_eq!(dependency.dst_subpass, 1);
+	assert_eq!(
+		dependency.src_stage_mask,
+		vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+	);
+	assert_eq!(
+		dependency.dst_stage_mask,
+		vk::PipelineStageFlags::FRAGMENT_SHADER
+	);
+	assert_eq!(
+		dependency.src_access_mask,
+		vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+	);
+	assert_eq!(
+		dependency.dst_access_mask,
+		vk::AccessFlags::INPUT_ATTACHMENT_READ
+	);
+	assert_eq!(dependency.dependency_flags, vk::DependencyFlags::BY_REGION);
+
+	let dependency2 = get_subpass_dependency2(0, 1, &global_barrier);
+	assert_eq!(dependency2.src_stage_mask, dependency.src_stage_mask);
+	assert_eq!(dependency2.dst_stage_mask, dependency.dst_stage_mask);
+	assert_eq!(dependency2.src_access_mask, dependency.src_access_mask);
+	assert_eq!(dependency2.dst_access_mask, dependency.dst_access_mask);
+	assert_eq!(dependency2.dependency_flags, vk::DependencyFlags::empty());
+}
+
+#[test]
+fn external_dependency_after_render_pass() {
+	// Render pass output sampled by a later compute dispatch
+	let dependency = get_subpass_dependency(
+		0,
+		vk::SUBPASS_EXTERNAL,
+		&vk_sync::GlobalBarrier {
+			previous_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+			next_accesses: &[
+				vk_sync::AccessType::ComputeShaderReadSampledImageOrUniformTexelBuffer,
+			],
+		},
+	);
+
+	assert_eq!(dependency.dst_subpass, vk::SUBPASS_EXTERNAL);
+	assert_eq!(
+		dependency.dst_stage_mask,
+		vk::PipelineStageFlags::COMPUTE_SHADER
+	);
+	assert_eq!(dependency.dst_access_mask, vk::AccessFlags::SHADER_READ);
+	assert_eq!(dependency.dependency_flags, vk::DependencyFlags::empty());
+}
+
+#[test]
+fn attachment_layouts() {
+	// Cleared every frame, then sampled after the render pass
+	let description = get_attachment_description(
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		vk_sync::ImageLayout::Optimal,
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		vk_sync::ImageLayout::Optimal,
+		true,
+	)
+	.format(vk::Format::R8G8B8A8_UNORM)
+	.load_op(vk::AttachmentLoadOp::CLEAR);
+
+	assert_eq!(description.initial_layout, vk::ImageLayout::UNDEFINED);
+	assert_eq!(
+		description.final_layout,
+		vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+	);
+
+	let reference = get_attachment_reference(
+		0,
+		&[vk_sync::AccessType::ColorAttachmentWrite],
+		vk_sync::ImageLayout::Optimal,
+	);
+	assert_eq!(reference.attachment, 0);
+	assert_eq!(reference.layout, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+
+	// Read only depth, also read as an input attachment
+	let reference = get_attachment_reference(
+		1,
+		&[
+			vk_sync::AccessType::DepthStencilAttachmentRead,
+			vk_sync::AccessType::FragmentShaderReadDepthStencilInputAttachment,
+		],
+		vk_sync::ImageLayout::Optimal,
+	);
+	assert_eq!(
+		reference.layout,
+		vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL
+	);
+
+	// Feedback loops need the general layout
+	let reference = get_attachment_reference(
+		2,
+		&[
+			vk_sync::AccessType::ColorAttachmentReadWrite,
+			vk_sync::AccessType::FragmentShaderReadColorInputAttachment,
+		],
+		vk_sync::ImageLayout::General,
+	);
+	assert_eq!(reference.layout, vk::ImageLayout::GENERAL);
+}
+
+#[test]
+#[should_panic(expected = "Invalid attachment reference")]
+fn conflicting_attachment_layouts() {
+	let _ = get_attachment_reference(
+		0,
+		&[
+			vk_sync::AccessType::ColorAttachmentWrite,
+			vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+		],
+		vk_sync::ImageLayout::Optimal,
+	);
+}
+
+#[test]
+#[should_panic(expected = "Invalid attachment reference")]
+fn optimal_feedback_loop() {
+	// The write and the read of a feedback loop only share the general layout
+	let _ = get_attachment_reference(
+		0,
+		&[
+			vk_sync::AccessType::ColorAttachmentReadWrite,
+			vk_sync::AccessType::FragmentShaderReadColorInputAttachment,
+		],
+		vk_sync::ImageLayout::Optimal,
+	);
+}
+
+#[test]
+#[should_panic(expected = "Invalid attachment description")]
+fn attachment_without_next_accesses() {
+	// Transient attachment, nothing reads it after the render pass
+	let _ = get_attachment_description(
+		&[],
+		vk_sync::ImageLayout::Optimal,
+		&[vk_sync::AccessType::Nothing],
+		vk_sync::ImageLayout::Optimal,
+		true,
+	);
+}
@@ -0,0 +1,272 @@
+mod common;
+
+use ash::vk;
+use ash::vk::Handle;
+use common::range;
+use std::cell::RefCell;
+use std::ffi::c_void;
+use vk_sync::rendering::{
+	RenderingAttachment, RenderingAttachmentEnd, ResolveAttachment, begin_rendering_barriers,
+	end_rendering_barriers,
+};
+
+thread_local! {
+	static IMAGE_BARRIERS: RefCell<Vec<(vk::Image, vk::ImageLayout, vk::ImageLayout)>> =
+		const { RefCell::new(Vec::new()) };
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "system" fn cmd_pipeline_barrier(
+	_command_buffer: vk::CommandBuffer,
+	_src_stage_mask: vk::PipelineStageFlags,
+	_dst_stage_mask: vk::PipelineStageFlags,
+	_dependency_flags: vk::DependencyFlags,
+	_memory_barrier_count: u32,
+	_p_memory_barriers: *const vk::MemoryBarrier,
+	_buffer_memory_barrier_count: u32,
+	_p_buffer_memory_barriers: *const vk::BufferMemoryBarrier,
+	image_memory_barrier_count: u32,
+	p_image_memory_barriers: *const vk::ImageMemoryBarrier,
+) {
+	let barriers = unsafe {
+		std::slice::from_raw_parts(p_image_memory_barriers, image_memory_barrier_count as usize)
+	};
+	IMAGE_BARRIERS.with(|recorded| {
+		recorded.borrow_mut().extend(
+			barriers
+				.iter()
+				.map(|barrier| (barrier.image, barrier.old_layout, barrier.new_layout)),
+		)
+	});
+}
+
+fn device() -> ash::Device {
+	common::load_device(&[(
+		c"vkCmdPipelineBarrier",
+		cmd_pipeline_barrier as *const c_void,
+	)])
+}
+
+#[test]
+fn multisampled_color_with_resolve_and_read_only_depth() {
+	let color = vk::Image::from_raw(1);
+	let resolve = vk::Image::from_raw(2);
+	let depth = vk::Image::from_raw(3);
+
+	let attachments = [
+		// Transient multisampled color, resolved into an image sampled last frame
+		RenderingAttachment {
+			image_view: vk::ImageView::from_raw(1),
+			image: color,
+			range: range(vk::ImageAspectFlags::COLOR),
+			next_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+			discard_contents: true,
+			resolve: Some(ResolveAttachment {
+				image_view: vk::ImageView::from_raw(2),
+				image: resolve,
+				range: range(vk::ImageAspectFlags::COLOR),
+				mode: vk::ResolveModeFlags::AVERAGE,
+				previous_accesses: &[
+					vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+				],
+				discard_contents: true,
+				..Default::default()
+			}),
+			..Default::default()
+		},
+		// Depth from a prepass, only tested against
+		RenderingAttachment {
+			image_view: vk::ImageView::from_raw(3),
+			image: depth,
+			range: range(vk::ImageAspectFlags::DEPTH),
+			previous_accesses: &[vk_sync::AccessType::DepthStencilAttachmentWrite],
+			next_accesses: &[vk_sync::AccessType::DepthStencilAttachmentRead],
+			..Default::default()
+		},
+	];
+
+	let infos = begin_rendering_barriers(&device(), vk::CommandBuffer::null(), &attachments);
+
+	assert_eq!(infos.len(), 2);
+	assert_eq!(infos[0].image_view, vk::ImageView::from_raw(1));
+	assert_eq!(
+		infos[0].image_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+	assert_eq!(infos[0].resolve_mode, vk::ResolveModeFlags::AVERAGE);
+	assert_eq!(infos[0].resolve_image_view, vk::ImageView::from_raw(2));
+	assert_eq!(
+		infos[0].resolve_image_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+	assert_eq!(
+		infos[1].image_layout,
+		vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL
+	);
+
+	IMAGE_BARRIERS.with(|recorded| {
+		assert_eq!(
+			*recorded.borrow(),
+			[
+				(
+					color,
+					vk::ImageLayout::UNDEFINED,
+					vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+				),
+				(
+					resolve,
+					vk::ImageLayout::UNDEFINED,
+					vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+				),
+				(
+					depth,
+					vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+					vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL
+				),
+			]
+		);
+	});
+
+	// After rendering, the resolved image is sampled again
+	let end_barrier = attachments[0].resolve.as_ref().unwrap().end_barrier(
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		vk_sync::ImageLayout::Optimal,
+	);
+	let (src_mask, dst_mask, barrier) = vk_sync::get_image_memory_barrier(&end_barrier);
+	assert_eq!(src_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::FRAGMENT_SHADER);
+	assert_eq!(
+		barrier.old_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+	);
+}
+
+#[test]
+fn end_rendering_transitions() {
+	let color = vk::Image::from_raw(1);
+	let resolve = vk::Image::from_raw(2);
+	let depth = vk::Image::from_raw(3);
+
+	let attachments = [
+		// Transient multisampled color, resolved into an image sampled afterwards
+		RenderingAttachment {
+			image: color,
+			range: range(vk::ImageAspectFlags::COLOR),
+			next_accesses: &[vk_sync::AccessType::ColorAttachmentWrite],
+			resolve: Some(ResolveAttachment {
+				image: resolve,
+				range: range(vk::ImageAspectFlags::COLOR),
+				mode: vk::ResolveModeFlags::AVERAGE,
+				..Default::default()
+			}),
+			..Default::default()
+		},
+		// Depth written, then tested against by the next pass
+		RenderingAttachment {
+			image: depth,
+			range: range(vk::ImageAspectFlags::DEPTH),
+			next_accesses: &[vk_sync::AccessType::DepthStencilAttachmentWrite],
+			..Default::default()
+		},
+	];
+
+	end_rendering_barriers(
+		&device(),
+		vk::CommandBuffer::null(),
+		&attachments,
+		&[
+			// The multisampled color is discarded, so it's left as it is
+			RenderingAttachmentEnd {
+				resolve_next_accesses: &[
+					vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+				],
+				..Default::default()
+			},
+			RenderingAttachmentEnd {
+				next_accesses: &[vk_sync::AccessType::DepthStencilAttachmentRead],
+				..Default::default()
+			},
+		],
+	);
+
+	IMAGE_BARRIERS.with(|recorded| {
+		assert_eq!(
+			*recorded.borrow(),
+			[
+				(
+					resolve,
+					vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+					vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+				),
+				(
+					depth,
+					vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+					vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL
+				),
+			]
+		);
+	});
+}
+
+#[test]
+fn depth_resolve_layout() {
+	let attachment = RenderingAttachment {
+		range: range(vk::ImageAspectFlags::DEPTH),
+		next_accesses: &[vk_sync::AccessType::DepthStencilAttachmentWrite],
+		resolve: Some(ResolveAttachment {
+			range: range(vk::ImageAspectFlags::DEPTH),
+			mode: vk::ResolveModeFlags::SAMPLE_ZERO,
+			..Default::default()
+		}),
+		..Default::default()
+	};
+
+	let info = attachment.attachment_info();
+
+	assert_eq!(
+		info.image_layout,
+		vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
+	);
+	assert_eq!(
+		info.resolve_image_layout,
+		vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
+	);
+
+	// Resolves are written in the color attachment output stage, even for depth
+	let resolve = attachment.resolve.as_ref().unwrap();
+	let (_, dst_mask, barrier) = vk_sync::get_image_memory_barrier(&resolve.begin_barrier());
+	assert_eq!(dst_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
+	);
+
+	let end_barrier = resolve.end_barrier(
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		vk_sync::ImageLayout::Optimal,
+	);
+	let (src_mask, _, barrier) = vk_sync::get_image_memory_barrier(&end_barrier);
+	assert_eq!(src_mask, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(
+		barrier.src_access_mask,
+		vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+	);
+	assert_eq!(
+		barrier.old_layout,
+		vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
+	);
+
+	let barrier2 = vk_sync::get_image_memory_barrier2(&end_barrier);
+	assert_eq!(
+		barrier2.src_stage_mask,
+		vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT
+	);
+	assert_eq!(
+		barrier2.src_access_mask,
+		vk::AccessFlags2::COLOR_ATTACHMENT_WRITE
+	);
+}
@@ -0,0 +1,99 @@
+//! Detection of barriers that don't order anything, and can be skipped.
+
+mod common;
+
+use ash::vk;
+use common::color_range;
+
+#[test]
+fn read_after_read_not_required() {
+	// Graphics read as vertex buffer, Compute read from storage buffer
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::VertexBuffer],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+	};
+
+	assert!(!vk_sync::is_barrier_required(&global_barrier));
+	assert!(vk_sync::get_required_memory_barrier(&global_barrier).is_none());
+}
+
+#[test]
+fn hazards_required() {
+	// Read after write, write after read and write after write all need a barrier
+	let hazards: [(&[vk_sync::AccessType], &[vk_sync::AccessType]); 3] = [
+		(
+			&[vk_sync::AccessType::ComputeShaderWrite],
+			&[vk_sync::AccessType::IndexBuffer],
+		),
+		(
+			&[vk_sync::AccessType::ComputeShaderReadOther],
+			&[vk_sync::AccessType::ComputeShaderWrite],
+		),
+		(
+			&[vk_sync::AccessType::TransferWrite],
+			&[vk_sync::AccessType::ComputeShaderWrite],
+		),
+	];
+
+	for (previous_accesses, next_accesses) in hazards.iter() {
+		let global_barrier = vk_sync::GlobalBarrier {
+			previous_accesses,
+			next_accesses,
+		};
+		assert!(vk_sync::is_barrier_required(&global_barrier));
+		assert!(vk_sync::get_required_memory_barrier(&global_barrier).is_some());
+	}
+}
+
+#[test]
+fn nothing_not_required() {
+	// Nothing to wait on before a write
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::Nothing],
+		next_accesses: &[vk_sync::AccessType::TransferWrite],
+	};
+
+	assert!(!vk_sync::is_barrier_required(&global_barrier));
+}
+
+#[test]
+fn buffer_queue_transfer_required() {
+	// Read only accesses, but ownership still has to be transferred
+	let mut buffer_barrier = vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::VertexBuffer],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		queue_transfer: vk_sync::QueueTransfer::Transfer { src: 0, dst: 1 },
+		buffer: vk::Buffer::null(),
+		offset: 0,
+		size: 64,
+	};
+	assert!(vk_sync::is_buffer_barrier_required(&buffer_barrier));
+
+	buffer_barrier.queue_transfer = vk_sync::QueueTransfer::Transfer { src: 0, dst: 0 };
+	assert!(!vk_sync::is_buffer_barrier_required(&buffer_barrier));
+	assert!(vk_sync::get_required_buffer_memory_barrier(&buffer_barrier).is_none());
+}
+
+#[test]
+fn image_layout_transition_required() {
+	// Sampled in a fragment shader, then read by a transfer - read only, but the layout changes
+	let mut image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[
+			vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+		],
+		next_accesses: &[vk_sync::AccessType::TransferRead],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: color_range(),
+	};
+	assert!(vk_sync::is_image_barrier_required(&image_barrier));
+
+	// In the general layout, the same accesses need no barrier at all
+	image_barrier.previous_layout = vk_sync::ImageLayout::General;
+	image_barrier.next_layout = vk_sync::ImageLayout::General;
+	assert!(!vk_sync::is_image_barrier_required(&image_barrier));
+	assert!(vk_sync::get_required_image_memory_barrier(&image_barrier).is_none());
+}
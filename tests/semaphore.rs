@@ -0,0 +1,67 @@
+use ash::vk;
+use ash::vk::Handle;
+
+#[test]
+fn swapchain_acquire_wait_stages() {
+	// Waiting on the acquire semaphore before rendering to the swapchain image
+	let (stages, stages2) =
+		vk_sync::semaphore_wait_stages(&[vk_sync::AccessType::ColorAttachmentWrite]);
+
+	assert_eq!(stages, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+	assert_eq!(stages2, vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT);
+}
+
+#[test]
+fn transfer_queue_upload_wait_stages() {
+	// Waiting on an upload from a transfer queue before vertex and index reads
+	let (stages, stages2) = vk_sync::semaphore_wait_stages(&[
+		vk_sync::AccessType::VertexBuffer,
+		vk_sync::AccessType::IndexBuffer,
+	]);
+
+	assert_eq!(stages, vk::PipelineStageFlags::VERTEX_INPUT);
+	assert_eq!(
+		stages2,
+		vk::PipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT | vk::PipelineStageFlags2::INDEX_INPUT
+	);
+}
+
+#[test]
+fn nothing_stages() {
+	// Both versions fall back to waiting on, or for, every command
+	let (stages, stages2) = vk_sync::semaphore_wait_stages(&[vk_sync::AccessType::Nothing]);
+	assert_eq!(stages, vk::PipelineStageFlags::ALL_COMMANDS);
+	assert_eq!(stages2, vk::PipelineStageFlags2::ALL_COMMANDS);
+
+	let (stages, stages2) = vk_sync::semaphore_signal_stages(&[]);
+	assert_eq!(stages, vk::PipelineStageFlags::ALL_COMMANDS);
+	assert_eq!(stages2, vk::PipelineStageFlags2::ALL_COMMANDS);
+}
+
+#[test]
+fn semaphore_submit_infos() {
+	// Compute writes signal a timeline semaphore, graphics waits before sampling
+	let timeline = vk::Semaphore::from_raw(1);
+	let signal =
+		vk_sync::semaphore_signal_info(timeline, 5, &[vk_sync::AccessType::ComputeShaderWrite]);
+
+	assert_eq!(signal.semaphore, timeline);
+	assert_eq!(signal.value, 5);
+	assert_eq!(signal.stage_mask, vk::PipelineStageFlags2::COMPUTE_SHADER);
+
+	let wait = vk_sync::semaphore_wait_info(
+		timeline,
+		5,
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+	);
+
+	assert_eq!(wait.value, 5);
+	assert_eq!(wait.stage_mask, vk::PipelineStageFlags2::FRAGMENT_SHADER);
+
+	// Binary semaphores ignore the value
+	let binary = vk::Semaphore::from_raw(2);
+	let wait = vk_sync::semaphore_wait_info(binary, 0, &[vk_sync::AccessType::TransferRead]);
+
+	assert_eq!(wait.semaphore, binary);
+	assert_eq!(wait.stage_mask, vk::PipelineStageFlags2::ALL_TRANSFER);
+}
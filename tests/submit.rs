@@ -0,0 +1,168 @@
+mod common;
+
+use ash::vk;
+use ash::vk::Handle;
+use vk_sync::submit::{SubmitBuilder, acquire_image_barrier};
+
+#[test]
+fn swapchain_frame() {
+	// Render to an acquired swapchain image, then signal the present semaphore
+	let acquire = vk::Semaphore::from_raw(1);
+	let present = vk::Semaphore::from_raw(2);
+	let command_buffer = vk::CommandBuffer::from_raw(3);
+
+	let submit = SubmitBuilder::new()
+		.wait_acquire(acquire, &[vk_sync::AccessType::ColorAttachmentWrite])
+		.command_buffer(command_buffer)
+		.signal(present, &[vk_sync::AccessType::ColorAttachmentWrite]);
+
+	assert!(!submit.uses_timeline_semaphores());
+
+	let submit_info = submit.submit_info();
+	assert_eq!(submit_info.wait_semaphore_count, 1);
+	assert_eq!(submit_info.command_buffer_count, 1);
+	assert_eq!(submit_info.signal_semaphore_count, 1);
+	let (wait_semaphores, wait_stages, signal_semaphores) = unsafe {
+		(
+			std::slice::from_raw_parts(submit_info.p_wait_semaphores, 1),
+			std::slice::from_raw_parts(submit_info.p_wait_dst_stage_mask, 1),
+			std::slice::from_raw_parts(submit_info.p_signal_semaphores, 1),
+		)
+	};
+	assert_eq!(wait_semaphores, [acquire]);
+	assert_eq!(
+		wait_stages,
+		[vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT]
+	);
+	assert_eq!(signal_semaphores, [present]);
+
+	let submit_info2 = submit.submit_info2();
+	assert_eq!(submit_info2.wait_semaphore_info_count, 1);
+	assert_eq!(submit_info2.signal_semaphore_info_count, 1);
+	let (wait_info, command_buffer_info, signal_info) = unsafe {
+		(
+			&*submit_info2.p_wait_semaphore_infos,
+			&*submit_info2.p_command_buffer_infos,
+			&*submit_info2.p_signal_semaphore_infos,
+		)
+	};
+	assert_eq!(wait_info.semaphore, acquire);
+	assert_eq!(
+		wait_info.stage_mask,
+		vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT
+	);
+	assert_eq!(command_buffer_info.command_buffer, command_buffer);
+	assert_eq!(signal_info.semaphore, present);
+	assert_eq!(
+		signal_info.stage_mask,
+		vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT
+	);
+}
+
+#[test]
+fn timeline_semaphores() {
+	// Async compute waits for an upload, and signals the next timeline value
+	let upload = vk::Semaphore::from_raw(1);
+	let timeline = vk::Semaphore::from_raw(2);
+	let binary = vk::Semaphore::from_raw(3);
+
+	let submit = SubmitBuilder::new()
+		.wait_timeline(upload, 7, &[vk_sync::AccessType::ComputeShaderReadOther])
+		.wait(binary, &[vk_sync::AccessType::TransferRead])
+		.command_buffer(vk::CommandBuffer::from_raw(4))
+		.signal_timeline(timeline, 8, &[vk_sync::AccessType::ComputeShaderWrite]);
+
+	assert!(submit.uses_timeline_semaphores());
+
+	let timeline_info = submit.timeline_semaphore_submit_info();
+	assert_eq!(timeline_info.wait_semaphore_value_count, 2);
+	assert_eq!(timeline_info.signal_semaphore_value_count, 1);
+	let (wait_values, signal_values) = unsafe {
+		(
+			std::slice::from_raw_parts(timeline_info.p_wait_semaphore_values, 2),
+			std::slice::from_raw_parts(timeline_info.p_signal_semaphore_values, 1),
+		)
+	};
+	assert_eq!(wait_values, [7, 0]);
+	assert_eq!(signal_values, [8]);
+
+	let submit_info = submit.submit_info();
+	let wait_stages = unsafe { std::slice::from_raw_parts(submit_info.p_wait_dst_stage_mask, 2) };
+	assert_eq!(
+		wait_stages,
+		[
+			vk::PipelineStageFlags::COMPUTE_SHADER,
+			vk::PipelineStageFlags::TRANSFER
+		]
+	);
+
+	let submit_info2 = submit.submit_info2();
+	let wait_infos = unsafe { std::slice::from_raw_parts(submit_info2.p_wait_semaphore_infos, 2) };
+	assert_eq!(wait_infos[0].value, 7);
+	assert_eq!(
+		wait_infos[1].stage_mask,
+		vk::PipelineStageFlags2::ALL_TRANSFER
+	);
+	let signal_info = unsafe { &*submit_info2.p_signal_semaphore_infos };
+	assert_eq!(signal_info.value, 8);
+	assert_eq!(
+		signal_info.stage_mask,
+		vk::PipelineStageFlags2::COMPUTE_SHADER
+	);
+}
+
+#[test]
+fn acquire_for_transfer() {
+	// Copy into an acquired swapchain image, so only transfers wait on the acquire
+	let acquire = vk::Semaphore::from_raw(1);
+	let submit = SubmitBuilder::new().wait_acquire(acquire, &[vk_sync::AccessType::TransferWrite]);
+
+	let image_barrier = acquire_image_barrier(
+		vk::Image::null(),
+		common::color_range(),
+		&[vk_sync::AccessType::TransferWrite],
+	);
+	let (src_stage_mask, _, barrier) = vk_sync::get_image_memory_barrier(&image_barrier);
+	assert_eq!(src_stage_mask, vk::PipelineStageFlags::TRANSFER);
+	assert_eq!(barrier.new_layout, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+
+	let submit_info = submit.submit_info();
+	let wait_stages = unsafe { std::slice::from_raw_parts(submit_info.p_wait_dst_stage_mask, 1) };
+	assert_eq!(wait_stages, [vk::PipelineStageFlags::TRANSFER]);
+
+	let submit_info2 = submit.submit_info2();
+	let wait_infos = unsafe { std::slice::from_raw_parts(submit_info2.p_wait_semaphore_infos, 1) };
+	assert_eq!(wait_infos[0].semaphore, acquire);
+	assert_eq!(
+		wait_infos[0].stage_mask,
+		vk::PipelineStageFlags2::ALL_TRANSFER
+	);
+}
+
+#[test]
+fn acquire_defaults_to_color_attachment_write() {
+	// Rendering to the acquired image, without spelling out the first accesses
+	let acquire = vk::Semaphore::from_raw(1);
+	let image = vk::Image::from_raw(2);
+	let submit = SubmitBuilder::new().wait_acquire(acquire, &[]);
+
+	let submit_info = submit.submit_info();
+	let wait_stages = unsafe { std::slice::from_raw_parts(submit_info.p_wait_dst_stage_mask, 1) };
+	assert_eq!(
+		wait_stages,
+		[vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT]
+	);
+
+	// The layout transition waits on the same stages as the semaphore
+	let image_barrier = acquire_image_barrier(image, common::color_range(), &[]);
+	let (src_stage_mask, dst_stage_mask, barrier) =
+		vk_sync::get_image_memory_barrier(&image_barrier);
+	assert_eq!(src_stage_mask, wait_stages[0]);
+	assert_eq!(dst_stage_mask, wait_stages[0]);
+	assert_eq!(barrier.image, image);
+	assert_eq!(barrier.old_layout, vk::ImageLayout::UNDEFINED);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+	);
+}
@@ -0,0 +1,209 @@
+//! Synchronization2 variants of the common synchronization examples on the Vulkan-Docs wiki: https://github.com/KhronosGroup/Vulkan-Docs/wiki/Synchronization-Examples.
+
+use ash::vk;
+
+#[test]
+fn compute_write_storage_graphics_read_index() {
+	// Compute write to storage buffer, Graphics read as index buffer
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::IndexBuffer],
+	};
+
+	let barrier = vk_sync::get_memory_barrier2(&global_barrier);
+
+	assert_eq!(
+		barrier.src_stage_mask,
+		vk::PipelineStageFlags2::COMPUTE_SHADER
+	);
+	assert_eq!(barrier.dst_stage_mask, vk::PipelineStageFlags2::INDEX_INPUT);
+	assert_eq!(
+		barrier.src_access_mask,
+		vk::AccessFlags2::SHADER_STORAGE_WRITE
+	);
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags2::INDEX_READ);
+}
+
+#[test]
+fn compute_read_storage_compute_write_storage() {
+	// Compute read from storage buffer, Compute write from storage buffer
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+	};
+
+	let barrier = vk_sync::get_memory_barrier2(&global_barrier);
+
+	assert_eq!(
+		barrier.src_stage_mask,
+		vk::PipelineStageFlags2::COMPUTE_SHADER
+	);
+	assert_eq!(
+		barrier.dst_stage_mask,
+		vk::PipelineStageFlags2::COMPUTE_SHADER
+	);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags2::empty());
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags2::empty());
+}
+
+#[test]
+fn nothing_transfer_read() {
+	// No previous accesses, so no source stages are required
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::Nothing],
+		next_accesses: &[vk_sync::AccessType::TransferRead],
+	};
+
+	let barrier = vk_sync::get_memory_barrier2(&global_barrier);
+
+	assert_eq!(barrier.src_stage_mask, vk::PipelineStageFlags2::NONE);
+	assert_eq!(
+		barrier.dst_stage_mask,
+		vk::PipelineStageFlags2::ALL_TRANSFER
+	);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags2::empty());
+	assert_eq!(barrier.dst_access_mask, vk::AccessFlags2::empty());
+}
+
+#[test]
+fn copy_write_graphics_read_vertex() {
+	// Copy to vertex buffer, Graphics read as vertex buffer
+	let buffer_barrier = vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::CopyWrite],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		queue_transfer: vk_sync::QueueTransfer::None,
+		buffer: vk::Buffer::null(),
+		offset: 0,
+		size: 256,
+	};
+
+	let barrier = vk_sync::get_buffer_memory_barrier2(&buffer_barrier);
+
+	assert_eq!(barrier.src_stage_mask, vk::PipelineStageFlags2::COPY);
+	assert_eq!(
+		barrier.dst_stage_mask,
+		vk::PipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT
+	);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags2::TRANSFER_WRITE);
+	assert_eq!(
+		barrier.dst_access_mask,
+		vk::AccessFlags2::VERTEX_ATTRIBUTE_READ
+	);
+	assert_eq!(barrier.size, 256);
+}
+
+#[test]
+fn blit_write_image_fragment_read_sampled() {
+	// Blit to image, Graphics fragment read from sampled image
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::BlitWrite],
+		next_accesses: &[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level: 0,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: 1,
+		},
+	};
+
+	let barrier = vk_sync::get_image_memory_barrier2(&image_barrier);
+
+	assert_eq!(barrier.src_stage_mask, vk::PipelineStageFlags2::BLIT);
+	assert_eq!(
+		barrier.dst_stage_mask,
+		vk::PipelineStageFlags2::FRAGMENT_SHADER
+	);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags2::TRANSFER_WRITE);
+	assert_eq!(
+		barrier.dst_access_mask,
+		vk::AccessFlags2::SHADER_SAMPLED_READ
+	);
+	assert_eq!(barrier.old_layout, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+	);
+}
+
+#[test]
+fn legacy_mapping_uses_coarse_transfer_stage() {
+	// The precise transfer accesses still map to TRANSFER with the legacy functions
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ResolveWrite],
+		next_accesses: &[vk_sync::AccessType::CopyRead],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level: 0,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: 1,
+		},
+	};
+
+	let (src_mask, dst_mask, barrier) = vk_sync::get_image_memory_barrier(&image_barrier);
+	let barrier2 = vk_sync::get_image_memory_barrier2(&image_barrier);
+
+	assert_eq!(src_mask, vk::PipelineStageFlags::TRANSFER);
+	assert_eq!(dst_mask, vk::PipelineStageFlags::TRANSFER);
+	assert_eq!(barrier.old_layout, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+	assert_eq!(barrier.new_layout, vk::ImageLayout::TRANSFER_SRC_OPTIMAL);
+	assert_eq!(barrier2.src_stage_mask, vk::PipelineStageFlags2::RESOLVE);
+	assert_eq!(barrier2.dst_stage_mask, vk::PipelineStageFlags2::COPY);
+	assert_eq!(barrier2.old_layout, barrier.old_layout);
+	assert_eq!(barrier2.new_layout, barrier.new_layout);
+}
+
+#[test]
+fn compute_write_shader_binding_table_read() {
+	// Compute write to shader binding table, Ray tracing dispatch
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::ShaderBindingTableRead],
+	};
+
+	let barrier = vk_sync::get_memory_barrier2(&global_barrier);
+
+	assert_eq!(
+		barrier.dst_stage_mask,
+		vk::PipelineStageFlags2::RAY_TRACING_SHADER_KHR
+	);
+	assert_eq!(
+		barrier.dst_access_mask,
+		vk::AccessFlags2::SHADER_BINDING_TABLE_READ_KHR
+	);
+}
+
+#[test]
+fn acceleration_structure_copy_ray_tracing_read() {
+	// Compacting copy of an acceleration structure, Ray tracing shader read
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[vk_sync::AccessType::AccelerationStructureCopyWrite],
+		next_accesses: &[vk_sync::AccessType::RayTracingShaderReadAccelerationStructure],
+	};
+
+	let barrier = vk_sync::get_memory_barrier2(&global_barrier);
+
+	assert_eq!(
+		barrier.src_stage_mask,
+		vk::PipelineStageFlags2::ACCELERATION_STRUCTURE_COPY_KHR
+	);
+	assert_eq!(
+		barrier.src_access_mask,
+		vk::AccessFlags2::ACCELERATION_STRUCTURE_WRITE_KHR
+	);
+	assert_eq!(
+		barrier.dst_access_mask,
+		vk::AccessFlags2::ACCELERATION_STRUCTURE_READ_KHR
+	);
+}
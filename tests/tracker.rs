@@ -0,0 +1,461 @@
+mod common;
+
+use ash::vk;
+use ash::vk::Handle;
+use common::color_range;
+use vk_sync::tracker::{BufferStateMap, ImageStateMap, ResourceTracker};
+
+#[test]
+fn buffer_write_then_reads() {
+	// Compute write to storage buffer, Graphics read as vertex buffer twice
+	let buffer = vk::Buffer::from_raw(1);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_buffer(buffer, &[vk_sync::AccessType::ComputeShaderWrite]);
+
+	tracker.transition(buffer, &[vk_sync::AccessType::VertexBuffer]);
+	{
+		let barriers = tracker.barriers();
+		let global_barrier = &barriers.global_barriers[0];
+		assert_eq!(
+			global_barrier.previous_accesses,
+			[vk_sync::AccessType::ComputeShaderWrite]
+		);
+		assert_eq!(
+			global_barrier.next_accesses,
+			[vk_sync::AccessType::VertexBuffer]
+		);
+		assert_eq!(barriers.global_barriers.len(), 1);
+	}
+	tracker.clear_barriers();
+
+	// Read after read of an access the write is already visible to
+	tracker.transition(buffer, &[vk_sync::AccessType::VertexBuffer]);
+	assert!(tracker.barriers().is_empty());
+}
+
+#[test]
+fn buffer_read_in_new_stage_after_write() {
+	// The compute write still needs to be made visible to the index buffer read
+	let buffer = vk::Buffer::from_raw(1);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_buffer(buffer, &[vk_sync::AccessType::ComputeShaderWrite]);
+
+	tracker.transition(buffer, &[vk_sync::AccessType::VertexBuffer]);
+	tracker.clear_barriers();
+	tracker.transition(buffer, &[vk_sync::AccessType::IndexBuffer]);
+
+	let barriers = tracker.barriers();
+	let global_barrier = &barriers.global_barriers[0];
+	assert_eq!(
+		global_barrier.previous_accesses,
+		[vk_sync::AccessType::ComputeShaderWrite]
+	);
+	assert_eq!(
+		global_barrier.next_accesses,
+		[vk_sync::AccessType::IndexBuffer]
+	);
+}
+
+#[test]
+fn untracked_buffer_reads_need_no_barrier() {
+	let mut tracker = ResourceTracker::new();
+
+	tracker.transition(
+		vk::Buffer::from_raw(1),
+		&[vk_sync::AccessType::TransferRead],
+	);
+	tracker.transition(
+		vk::Buffer::from_raw(2),
+		&[vk_sync::AccessType::TransferWrite],
+	);
+
+	assert!(tracker.barriers().is_empty());
+	assert_eq!(
+		tracker.last_accesses(vk::Buffer::from_raw(1)),
+		Some(&[vk_sync::AccessType::TransferRead][..])
+	);
+}
+
+#[test]
+fn buffer_write_after_reads() {
+	// Graphics reads of one buffer, then a transfer write - a WAR hazard on the reads
+	let buffer = vk::Buffer::from_raw(1);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_buffer(
+		buffer,
+		&[
+			vk_sync::AccessType::VertexBuffer,
+			vk_sync::AccessType::IndexBuffer,
+		],
+	);
+
+	tracker.transition(buffer, &[vk_sync::AccessType::TransferWrite]);
+
+	let barriers = tracker.barriers();
+	let global_barrier = &barriers.global_barriers[0];
+	assert_eq!(
+		global_barrier.previous_accesses,
+		[
+			vk_sync::AccessType::VertexBuffer,
+			vk_sync::AccessType::IndexBuffer
+		]
+	);
+	assert_eq!(
+		global_barrier.next_accesses,
+		[vk_sync::AccessType::TransferWrite]
+	);
+}
+
+#[test]
+fn conflicting_buffers_queue_another_global_barrier() {
+	// Two different writes can't be folded into one global barrier
+	let first = vk::Buffer::from_raw(1);
+	let second = vk::Buffer::from_raw(2);
+	let third = vk::Buffer::from_raw(3);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_buffer(first, &[vk_sync::AccessType::ComputeShaderWrite]);
+	tracker.register_buffer(second, &[vk_sync::AccessType::TransferWrite]);
+	tracker.register_buffer(third, &[vk_sync::AccessType::ComputeShaderWrite]);
+
+	tracker.transition(first, &[vk_sync::AccessType::IndirectBuffer]);
+	tracker.transition(second, &[vk_sync::AccessType::VertexBuffer]);
+	tracker.transition(third, &[vk_sync::AccessType::IndexBuffer]);
+
+	// The third buffer is folded into the first global barrier again
+	let barriers = tracker.barriers();
+	assert!(barriers.image_barriers.is_empty());
+	assert_eq!(barriers.global_barriers.len(), 2);
+	assert_eq!(
+		barriers.global_barriers[0].previous_accesses,
+		[vk_sync::AccessType::ComputeShaderWrite]
+	);
+	assert_eq!(
+		barriers.global_barriers[0].next_accesses,
+		[
+			vk_sync::AccessType::IndirectBuffer,
+			vk_sync::AccessType::IndexBuffer
+		]
+	);
+	assert_eq!(
+		barriers.global_barriers[1].previous_accesses,
+		[vk_sync::AccessType::TransferWrite]
+	);
+	assert_eq!(
+		barriers.global_barriers[1].next_accesses,
+		[vk_sync::AccessType::VertexBuffer]
+	);
+}
+
+#[test]
+fn image_layout_transitions() {
+	// Transfer write to image, Graphics fragment read from sampled image
+	let image = vk::Image::from_raw(1);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_image(
+		image,
+		color_range(),
+		vk_sync::ImageLayout::Optimal,
+		&[vk_sync::AccessType::Nothing],
+	);
+
+	tracker.transition(image, &[vk_sync::AccessType::TransferWrite]);
+	{
+		let barriers = tracker.barriers();
+		assert!(barriers.global_barriers.is_empty());
+		assert_eq!(barriers.image_barriers.len(), 1);
+		let (_, _, barrier) = vk_sync::get_image_memory_barrier(&barriers.image_barriers[0]);
+		assert_eq!(barrier.old_layout, vk::ImageLayout::UNDEFINED);
+		assert_eq!(barrier.new_layout, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+	}
+	tracker.clear_barriers();
+
+	tracker.transition(
+		image,
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+	);
+	let barriers = tracker.barriers();
+	assert_eq!(barriers.image_barriers.len(), 1);
+	let (_, _, barrier) = vk_sync::get_image_memory_barrier(&barriers.image_barriers[0]);
+	assert_eq!(barrier.old_layout, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+	);
+	assert_eq!(barrier.src_access_mask, vk::AccessFlags::TRANSFER_WRITE);
+}
+
+#[test]
+fn general_image_without_transition_uses_global_barrier() {
+	// Compute write to storage image, Compute read from storage image, both in GENERAL
+	let image = vk::Image::from_raw(1);
+	let mut tracker = ResourceTracker::new();
+	tracker.register_image(
+		image,
+		color_range(),
+		vk_sync::ImageLayout::General,
+		&[vk_sync::AccessType::ComputeShaderWrite],
+	);
+
+	tracker.transition(image, &[vk_sync::AccessType::ComputeShaderReadOther]);
+
+	let barriers = tracker.barriers();
+	assert!(barriers.image_barriers.is_empty());
+	let global_barrier = &barriers.global_barriers[0];
+	assert_eq!(
+		global_barrier.previous_accesses,
+		[vk_sync::AccessType::ComputeShaderWrite]
+	);
+}
+
+#[test]
+#[should_panic(expected = "is not registered")]
+fn unregistered_image() {
+	let mut tracker = ResourceTracker::new();
+	tracker.transition(vk::Image::from_raw(1), &[vk_sync::AccessType::TransferRead]);
+}
+
+fn mip_range(base_mip_level: u32, level_count: u32) -> vk::ImageSubresourceRange {
+	vk::ImageSubresourceRange {
+		base_mip_level,
+		level_count,
+		..color_range()
+	}
+}
+
+#[test]
+fn image_state_map_mip_chain() {
+	// Transfer write to mip 0, while the other mips are sampled
+	let mut map = ImageStateMap::new(
+		vk::ImageAspectFlags::COLOR,
+		4,
+		1,
+		vk_sync::ImageLayout::Optimal,
+		&[],
+	);
+
+	let transitions = map.transition(
+		mip_range(0, 1),
+		&[vk_sync::AccessType::TransferWrite],
+		vk_sync::ImageLayout::Optimal,
+	);
+	assert_eq!(transitions.len(), 1);
+	assert_eq!(transitions[0].range.level_count, 1);
+
+	let transitions = map.transition(
+		mip_range(1, vk::REMAINING_MIP_LEVELS),
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		vk_sync::ImageLayout::Optimal,
+	);
+	assert_eq!(transitions.len(), 1);
+	assert_eq!(transitions[0].range.base_mip_level, 1);
+	assert_eq!(transitions[0].range.level_count, 3);
+
+	// Only mip 0 still needs a transition to be sampled
+	let transitions = map.transition(
+		map.full_range(),
+		&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		vk_sync::ImageLayout::Optimal,
+	);
+	assert_eq!(transitions.len(), 1);
+	assert_eq!(transitions[0].range.base_mip_level, 0);
+	assert_eq!(transitions[0].range.level_count, 1);
+	assert_eq!(
+		transitions[0].previous_accesses,
+		[vk_sync::AccessType::TransferWrite]
+	);
+
+	let image = vk::Image::from_raw(1);
+	let (_, _, barrier) = vk_sync::get_image_memory_barrier(&transitions[0].to_barrier(image));
+	assert_eq!(barrier.image, image);
+	assert_eq!(barrier.old_layout, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+	assert_eq!(
+		barrier.new_layout,
+		vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+	);
+}
+
+#[test]
+fn image_state_map_coalesces_layers_and_mips() {
+	let mut map = ImageStateMap::new(
+		vk::ImageAspectFlags::COLOR,
+		3,
+		4,
+		vk_sync::ImageLayout::Optimal,
+		&[vk_sync::AccessType::Nothing],
+	);
+
+	let transitions = map.transition(
+		vk::ImageSubresourceRange {
+			aspect_mask: vk::ImageAspectFlags::COLOR,
+			base_mip_level: 0,
+			level_count: vk::REMAINING_MIP_LEVELS,
+			base_array_layer: 1,
+			layer_count: 2,
+		},
+		&[vk_sync::AccessType::TransferWrite],
+		vk_sync::ImageLayout::Optimal,
+	);
+
+	assert_eq!(transitions.len(), 1);
+	let range = transitions[0].range;
+	assert_eq!((range.base_mip_level, range.level_count), (0, 3));
+	assert_eq!((range.base_array_layer, range.layer_count), (1, 2));
+	assert_eq!(
+		map.subresource_state(vk::ImageAspectFlags::COLOR, 2, 2),
+		(
+			&[vk_sync::AccessType::TransferWrite][..],
+			vk_sync::ImageLayout::Optimal
+		)
+	);
+}
+
+#[test]
+fn image_state_map_splits_on_previous_state() {
+	// Layer 0 was written by a transfer, the remaining layers are untouched
+	let mut map = ImageStateMap::new(
+		vk::ImageAspectFlags::COLOR,
+		1,
+		4,
+		vk_sync::ImageLayout::Optimal,
+		&[],
+	);
+	map.transition(
+		color_range(),
+		&[vk_sync::AccessType::TransferWrite],
+		vk_sync::ImageLayout::Optimal,
+	);
+
+	let transitions = map.transition(
+		map.full_range(),
+		&[vk_sync::AccessType::ColorAttachmentWrite],
+		vk_sync::ImageLayout::Optimal,
+	);
+
+	assert_eq!(transitions.len(), 2);
+	assert_eq!(transitions[0].range.layer_count, 1);
+	assert_eq!(
+		transitions[0].previous_accesses,
+		[vk_sync::AccessType::TransferWrite]
+	);
+	assert_eq!(transitions[1].range.base_array_layer, 1);
+	assert_eq!(transitions[1].range.layer_count, 3);
+	assert!(transitions[1].previous_accesses.is_empty());
+}
+
+#[test]
+fn image_state_map_merges_aspects() {
+	let mut map = ImageStateMap::new(
+		vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL,
+		2,
+		1,
+		vk_sync::ImageLayout::Optimal,
+		&[],
+	);
+
+	let transitions = map.transition(
+		map.full_range(),
+		&[vk_sync::AccessType::DepthStencilAttachmentWrite],
+		vk_sync::ImageLayout::Optimal,
+	);
+
+	assert_eq!(transitions.len(), 1);
+	assert_eq!(
+		transitions[0].range.aspect_mask,
+		vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL
+	);
+	assert_eq!(transitions[0].range.level_count, 2);
+}
+
+#[test]
+fn buffer_state_map_splits_partial_overlaps() {
+	// Upload to a whole buffer, then vertex reads of a sub-allocation
+	let mut map = BufferStateMap::new(1024, &[vk_sync::AccessType::TransferWrite]);
+
+	let transition = map.transition(256, 256, &[vk_sync::AccessType::VertexBuffer]);
+	assert_eq!(transition.ranges().len(), 1);
+	assert_eq!(transition.ranges()[0].offset, 256);
+	assert_eq!(transition.ranges()[0].size, 256);
+
+	// The rest of the buffer still needs the upload made visible
+	let transition = map.transition(
+		0,
+		vk::WHOLE_SIZE as usize,
+		&[vk_sync::AccessType::VertexBuffer],
+	);
+	let ranges: Vec<(usize, usize)> = transition
+		.ranges()
+		.iter()
+		.map(|range| (range.offset, range.size))
+		.collect();
+	assert_eq!(ranges, [(0, 256), (512, 512)]);
+
+	let global_barrier = transition.global_barrier().unwrap();
+	assert_eq!(
+		global_barrier.previous_accesses,
+		[vk_sync::AccessType::TransferWrite]
+	);
+	assert_eq!(
+		global_barrier.next_accesses,
+		[vk_sync::AccessType::VertexBuffer]
+	);
+
+	// Every range is in the same state again, so a write covers it in one range
+	let transition = map.transition(0, 1024, &[vk_sync::AccessType::TransferWrite]);
+	assert_eq!(transition.ranges().len(), 1);
+	assert_eq!(transition.ranges()[0].size, 1024);
+}
+
+#[test]
+fn buffer_state_map_independent_ranges() {
+	// Sub-allocations written and read separately don't need barriers for each other
+	let mut map = BufferStateMap::new(1024, &[]);
+
+	assert!(
+		map.transition(0, 512, &[vk_sync::AccessType::TransferWrite])
+			.is_empty()
+	);
+	assert!(
+		map.transition(512, 512, &[vk_sync::AccessType::TransferRead])
+			.is_empty()
+	);
+	assert_eq!(map.last_accesses(0), [vk_sync::AccessType::TransferWrite]);
+	assert_eq!(map.last_accesses(768), [vk_sync::AccessType::TransferRead]);
+}
+
+#[test]
+fn buffer_state_map_falls_back_to_global_barrier_per_range() {
+	// One range last written by compute and another last read as vertices can't
+	// share a global barrier
+	let mut map = BufferStateMap::new(1024, &[]);
+	map.transition(0, 512, &[vk_sync::AccessType::ComputeShaderWrite]);
+	map.transition(512, 512, &[vk_sync::AccessType::VertexBuffer]);
+
+	let transition = map.transition(0, 1024, &[vk_sync::AccessType::TransferWrite]);
+	assert!(transition.global_barrier().is_none());
+
+	let global_barriers = transition.global_barriers();
+	assert_eq!(global_barriers.len(), 2);
+	assert_eq!(
+		global_barriers[0].previous_accesses,
+		[vk_sync::AccessType::ComputeShaderWrite]
+	);
+	assert_eq!(
+		global_barriers[1].previous_accesses,
+		[vk_sync::AccessType::VertexBuffer]
+	);
+
+	// Ranges are only turned into buffer barriers for ownership transfers
+	let buffer = vk::Buffer::from_raw(1);
+	let queue_transfer = vk_sync::QueueTransfer::Transfer { src: 0, dst: 1 };
+	let buffer_barriers = transition.buffer_barriers(buffer, queue_transfer);
+	assert_eq!(buffer_barriers.len(), 2);
+	assert_eq!(buffer_barriers[1].offset, 512);
+	assert_eq!(buffer_barriers[1].queue_transfer, queue_transfer);
+}
+
+#[test]
+#[should_panic(expected = "is outside of the buffer")]
+fn buffer_state_map_range_overflow() {
+	let mut map = BufferStateMap::new(1024, &[]);
+	map.transition(512, usize::MAX - 256, &[vk_sync::AccessType::TransferWrite]);
+}
@@ -0,0 +1,281 @@
+//! Checks performed by the opt-in `validation` feature.
+#![cfg(feature = "validation")]
+
+mod common;
+
+use ash::vk;
+use common::{NullDevice, color_range};
+use std::sync::{Arc, Mutex};
+use vk_sync::validation::{BarrierLocation, ValidationError, ValidationMessage};
+
+// The callback is global, so tests installing one can't run concurrently
+static CALLBACK_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn nothing_mixed_with_other_accesses() {
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[
+			vk_sync::AccessType::Nothing,
+			vk_sync::AccessType::ComputeShaderWrite,
+		],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+	};
+
+	assert_eq!(
+		vk_sync::validation::validate_global_barrier(&global_barrier),
+		[ValidationError::NothingMixedWithOtherAccesses]
+	);
+}
+
+#[test]
+fn discard_with_queue_transfer() {
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderReadSampledImageOrUniformTexelBuffer],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: true,
+		queue_transfer: vk_sync::QueueTransfer::Transfer { src: 0, dst: 1 },
+		image: vk::Image::null(),
+		range: color_range(),
+	};
+
+	assert_eq!(
+		vk_sync::validation::validate_image_barrier(&image_barrier),
+		[ValidationError::DiscardWithQueueTransfer]
+	);
+}
+
+#[test]
+fn image_barrier_without_transition() {
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::ComputeShaderWrite],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: color_range(),
+	};
+
+	assert_eq!(
+		vk_sync::validation::validate_image_barrier(&image_barrier),
+		[ValidationError::ImageBarrierWithoutTransition]
+	);
+}
+
+#[test]
+fn valid_buffer_barrier() {
+	let buffer_barrier = vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		queue_transfer: vk_sync::QueueTransfer::Transfer { src: 1, dst: 0 },
+		buffer: vk::Buffer::null(),
+		offset: 0,
+		size: 64,
+	};
+
+	assert!(vk_sync::validation::validate_buffer_barrier(&buffer_barrier).is_empty());
+}
+
+#[test]
+fn by_region_framebuffer_space() {
+	// Color attachment write, input attachment read in the next subpass
+	assert!(
+		vk_sync::validation::validate_dependency_flags(
+			vk::DependencyFlags::BY_REGION,
+			&[vk_sync::AccessType::ColorAttachmentWrite],
+			&[vk_sync::AccessType::FragmentShaderReadColorInputAttachment],
+		)
+		.is_empty()
+	);
+
+	// Compute isn't framebuffer-local
+	assert_eq!(
+		vk_sync::validation::validate_dependency_flags(
+			vk::DependencyFlags::BY_REGION,
+			&[vk_sync::AccessType::ComputeShaderWrite],
+			&[vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+		),
+		[ValidationError::ByRegionOutsideFramebufferSpace]
+	);
+
+	// Other flags don't restrict the stages
+	assert!(
+		vk_sync::validation::validate_dependency_flags(
+			vk::DependencyFlags::VIEW_LOCAL,
+			&[vk_sync::AccessType::ComputeShaderWrite],
+			&[vk_sync::AccessType::IndirectBuffer],
+		)
+		.is_empty()
+	);
+}
+
+#[test]
+fn recording_reports_through_callback() {
+	let _lock = CALLBACK_LOCK.lock().unwrap();
+	let messages: Arc<Mutex<Vec<ValidationMessage>>> = Arc::default();
+	let captured = messages.clone();
+	vk_sync::validation::set_callback(move |message| captured.lock().unwrap().push(*message));
+
+	vk_sync::cmd::pipeline_barrier2(
+		&NullDevice,
+		vk::CommandBuffer::null(),
+		None,
+		&[vk_sync::BufferBarrier {
+			previous_accesses: &[vk_sync::AccessType::TransferWrite],
+			next_accesses: &[
+				vk_sync::AccessType::Nothing,
+				vk_sync::AccessType::VertexBuffer,
+			],
+			queue_transfer: vk_sync::QueueTransfer::None,
+			buffer: vk::Buffer::null(),
+			offset: 0,
+			size: 64,
+		}],
+		&[],
+	);
+
+	vk_sync::validation::clear_callback();
+
+	assert_eq!(
+		*messages.lock().unwrap(),
+		[ValidationMessage {
+			location: BarrierLocation::Buffer(0),
+			error: ValidationError::NothingMixedWithOtherAccesses,
+		},]
+	);
+}
+
+#[test]
+fn callback_can_clear_itself() {
+	let _lock = CALLBACK_LOCK.lock().unwrap();
+	let calls: Arc<Mutex<usize>> = Arc::default();
+	let captured = calls.clone();
+	vk_sync::validation::set_callback(move |_| {
+		*captured.lock().unwrap() += 1;
+		vk_sync::validation::clear_callback();
+	});
+
+	let global_barrier = vk_sync::GlobalBarrier {
+		previous_accesses: &[
+			vk_sync::AccessType::Nothing,
+			vk_sync::AccessType::ComputeShaderWrite,
+		],
+		next_accesses: &[vk_sync::AccessType::ComputeShaderReadOther],
+	};
+
+	// The second message is dropped, as the first cleared the callback
+	for _ in 0..2 {
+		vk_sync::cmd::pipeline_barrier2(
+			&NullDevice,
+			vk::CommandBuffer::null(),
+			Some(global_barrier.clone()),
+			&[],
+			&[],
+		);
+	}
+
+	assert_eq!(*calls.lock().unwrap(), 1);
+}
+
+#[test]
+fn batch_reports_pushed_index() {
+	let _lock = CALLBACK_LOCK.lock().unwrap();
+	let messages: Arc<Mutex<Vec<ValidationMessage>>> = Arc::default();
+	let captured = messages.clone();
+	vk_sync::validation::set_callback(move |message| captured.lock().unwrap().push(*message));
+
+	let mut batch = vk_sync::cmd::BarrierBatch::new();
+
+	// Read after read isn't required, but still counts towards the index
+	batch.push_buffer(&vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::VertexBuffer],
+		next_accesses: &[vk_sync::AccessType::VertexBuffer],
+		queue_transfer: vk_sync::QueueTransfer::None,
+		buffer: vk::Buffer::null(),
+		offset: 0,
+		size: 64,
+	});
+	batch.push_buffer(&vk_sync::BufferBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[
+			vk_sync::AccessType::Nothing,
+			vk_sync::AccessType::VertexBuffer,
+		],
+		queue_transfer: vk_sync::QueueTransfer::None,
+		buffer: vk::Buffer::null(),
+		offset: 0,
+		size: 64,
+	});
+
+	// The second image barrier is deduplicated, but still reported at its own index
+	let image_barrier = vk_sync::ImageBarrier {
+		previous_accesses: &[vk_sync::AccessType::TransferWrite],
+		next_accesses: &[
+			vk_sync::AccessType::Nothing,
+			vk_sync::AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer,
+		],
+		previous_layout: vk_sync::ImageLayout::Optimal,
+		next_layout: vk_sync::ImageLayout::Optimal,
+		discard_contents: false,
+		queue_transfer: vk_sync::QueueTransfer::None,
+		image: vk::Image::null(),
+		range: color_range(),
+	};
+	batch.push_image(&image_barrier);
+	batch.push_image(&image_barrier);
+	assert_eq!(batch.image_barriers().len(), 1);
+
+	vk_sync::validation::clear_callback();
+
+	let location = |message: &ValidationMessage| message.location;
+	assert_eq!(
+		messages
+			.lock()
+			.unwrap()
+			.iter()
+			.map(location)
+			.collect::<Vec<_>>(),
+		[
+			BarrierLocation::Buffer(1),
+			BarrierLocation::Image(0),
+			BarrierLocation::Image(1)
+		]
+	);
+}
+
+#[test]
+fn split_barrier_reports_once() {
+	let _lock = CALLBACK_LOCK.lock().unwrap();
+	let messages: Arc<Mutex<Vec<ValidationMessage>>> = Arc::default();
+	let captured = messages.clone();
+	vk_sync::validation::set_callback(move |message| captured.lock().unwrap().push(*message));
+
+	let split_barrier = vk_sync::cmd::SplitBarrier {
+		global_barrier: Some(vk_sync::GlobalBarrier {
+			previous_accesses: &[
+				vk_sync::AccessType::Nothing,
+				vk_sync::AccessType::ComputeShaderWrite,
+			],
+			next_accesses: &[vk_sync::AccessType::IndirectBuffer],
+		}),
+		..Default::default()
+	};
+
+	// Validated when the event is set, but not again when it is waited on
+	split_barrier
+		.begin2(&NullDevice, vk::CommandBuffer::null(), vk::Event::null())
+		.end2(&NullDevice, vk::CommandBuffer::null());
+
+	vk_sync::validation::clear_callback();
+
+	assert_eq!(
+		*messages.lock().unwrap(),
+		[ValidationMessage {
+			location: BarrierLocation::Global,
+			error: ValidationError::NothingMixedWithOtherAccesses,
+		}]
+	);
+}